@@ -16,6 +16,6 @@ pub fn main() {
         println!();
     }
 
-    let kerning = font.get_kerning('A', 'B');
+    let kerning = font.kerning('A', 'B');
     //Only works with fonts that have a kern table
 }
\ No newline at end of file
@@ -0,0 +1,723 @@
+mod tables;
+
+use core::ops::Range;
+
+use crate::shaping::Direction;
+use crate::vec;
+use crate::Vec;
+
+use tables::{BIDI_CLASSES, BRACKETS, MIRRORS};
+
+/// Deepest explicit embedding level allowed by UAX #9.
+const MAX_DEPTH: u8 = 125;
+
+/// Maximum number of nested open brackets tracked when pairing brackets.
+const MAX_BRACKET_DEPTH: usize = 63;
+
+/// The Unicode `Bidi_Class` of a character, using the UAX #9 abbreviations.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BidiClass {
+    L,
+    R,
+    AL,
+    EN,
+    ES,
+    ET,
+    AN,
+    CS,
+    NSM,
+    BN,
+    B,
+    S,
+    WS,
+    ON,
+    LRE,
+    LRO,
+    RLE,
+    RLO,
+    PDF,
+    LRI,
+    RLI,
+    FSI,
+    PDI,
+}
+
+use BidiClass::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum BracketType {
+    Open,
+    Close,
+}
+
+pub fn bidi_class(c: char) -> BidiClass {
+    let cp = c as u32;
+    match BIDI_CLASSES.binary_search_by(|&(start, end, _)| {
+        if cp < start {
+            core::cmp::Ordering::Greater
+        } else if cp > end {
+            core::cmp::Ordering::Less
+        } else {
+            core::cmp::Ordering::Equal
+        }
+    }) {
+        Ok(i) => BIDI_CLASSES[i].2,
+        Err(_) => L,
+    }
+}
+
+/// The `Bidi_Mirroring_Glyph` of a character, such as `)` for `(`.
+pub fn mirrored_char(c: char) -> Option<char> {
+    let i = MIRRORS.binary_search_by_key(&(c as u32), |&(from, _)| from).ok()?;
+    char::from_u32(MIRRORS[i].1)
+}
+
+/// The paired bracket of a character and whether it opens or closes the pair.
+pub(crate) fn bracket(c: char) -> Option<(u32, BracketType)> {
+    // U+2329/U+232A are canonically equivalent to U+3008/U+3009
+    let cp = match c as u32 {
+        0x2329 => 0x3008,
+        0x232A => 0x3009,
+        cp => cp,
+    };
+
+    let i = BRACKETS.binary_search_by_key(&cp, |&(from, _, _)| from).ok()?;
+    let (_, pair, kind) = BRACKETS[i];
+    Some((pair, kind))
+}
+
+fn is_removed(class: BidiClass) -> bool {
+    matches!(class, RLE | LRE | RLO | LRO | PDF | BN)
+}
+
+fn is_isolate_initiator(class: BidiClass) -> bool {
+    matches!(class, LRI | RLI | FSI)
+}
+
+/// Direction of a resolved type for the neutral and bracket rules, where
+/// numbers count as right-to-left.
+fn strong_direction(class: BidiClass) -> Option<BidiClass> {
+    match class {
+        L => Some(L),
+        R | AL | EN | AN => Some(R),
+        _ => None,
+    }
+}
+
+fn level_direction(level: u8) -> BidiClass {
+    if level % 2 == 1 { R } else { L }
+}
+
+/// The least odd (right-to-left) or even (left-to-right) level above `level`.
+fn next_level(level: u8, rtl: bool) -> u8 {
+    if rtl { (level + 1) | 1 } else { (level + 2) & !1 }
+}
+
+/// A paragraph of the text and its base embedding level.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BidiParagraph {
+    /// Byte range, including the paragraph separator that ends it.
+    pub range: Range<usize>,
+    pub level: u8,
+}
+
+/// A run of text at one embedding level. Odd levels are right-to-left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BidiRun {
+    /// Byte range in the source text.
+    pub range: Range<usize>,
+    pub level: u8,
+}
+
+impl BidiRun {
+    pub fn direction(&self) -> Direction {
+        if self.level % 2 == 1 { Direction::RightToLeft } else { Direction::LeftToRight }
+    }
+}
+
+/// Embedding levels resolved by the Unicode Bidirectional Algorithm (UAX #9).
+#[derive(Clone, Debug)]
+pub struct BidiInfo {
+    /// Resolved embedding level of every byte of the text.
+    pub levels: Vec<u8>,
+    pub paragraphs: Vec<BidiParagraph>,
+    /// Original bidi class of every byte of the text.
+    classes: Vec<BidiClass>,
+}
+
+impl BidiInfo {
+    /// Resolves the embedding levels of `text`. Each paragraph takes its
+    /// direction from `base`, or from its first strong character when `None`.
+    pub fn new(text: &str, base: Option<Direction>) -> Self {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let original: Vec<BidiClass> = chars.iter().map(|&(_, c)| bidi_class(c)).collect();
+
+        let mut levels = vec![0; text.len()];
+        let mut classes = vec![L; text.len()];
+        let mut paragraphs = Vec::new();
+
+        let mut start = 0;
+        while start < chars.len() {
+            let end = original[start..].iter().position(|&class| class == B).map_or(chars.len(), |n| start + n + 1);
+
+            let paragraph: Vec<char> = chars[start..end].iter().map(|&(_, c)| c).collect();
+            let (level, char_levels) = resolve_paragraph(&paragraph, &original[start..end], base);
+
+            let byte_end = chars.get(end).map_or(text.len(), |&(offset, _)| offset);
+            paragraphs.push(BidiParagraph { range: chars[start].0..byte_end, level });
+
+            for (k, &(offset, c)) in chars[start..end].iter().enumerate() {
+                let bytes = offset..offset + c.len_utf8();
+                levels[bytes.clone()].fill(char_levels[k]);
+                classes[bytes].fill(original[start + k]);
+            }
+
+            start = end;
+        }
+
+        BidiInfo { levels, paragraphs, classes }
+    }
+
+    /// Splits one line of the text into runs in visual (left-to-right
+    /// display) order. `line` must lie within a single paragraph.
+    pub fn visual_runs(&self, line: Range<usize>) -> Vec<BidiRun> {
+        let line = line.start.min(self.levels.len())..line.end.min(self.levels.len());
+        if line.is_empty() {
+            return Vec::new();
+        }
+
+        let paragraph_level = self.paragraphs.iter()
+            .find(|p| p.range.contains(&line.start))
+            .map_or(0, |p| p.level);
+
+        // L1: separators and trailing whitespace go back to the paragraph level
+        let mut levels = self.levels[line.clone()].to_vec();
+        let mut whitespace_start = None;
+
+        for (k, &class) in self.classes[line.clone()].iter().enumerate() {
+            match class {
+                WS | FSI | LRI | RLI | PDI | BN | LRE | RLE | LRO | RLO | PDF => {
+                    whitespace_start.get_or_insert(k);
+                }
+                S | B => {
+                    levels[whitespace_start.unwrap_or(k)..=k].fill(paragraph_level);
+                    whitespace_start = None;
+                }
+                _ => whitespace_start = None,
+            }
+        }
+
+        if let Some(k) = whitespace_start {
+            levels[k..].fill(paragraph_level);
+        }
+
+        let mut runs: Vec<BidiRun> = Vec::new();
+        for (k, &level) in levels.iter().enumerate() {
+            match runs.last_mut() {
+                Some(run) if run.level == level => run.range.end = line.start + k + 1,
+                _ => runs.push(BidiRun { range: line.start + k..line.start + k + 1, level }),
+            }
+        }
+
+        // L2: reverse every sequence at or above each odd level, highest first
+        let highest = runs.iter().map(|run| run.level).max().unwrap_or(0);
+        let lowest_odd = runs.iter().map(|run| run.level).min().unwrap_or(0) | 1;
+
+        for level in (lowest_odd..=highest).rev() {
+            let mut i = 0;
+            while i < runs.len() {
+                if runs[i].level < level {
+                    i += 1;
+                    continue;
+                }
+
+                let start = i;
+                while i < runs.len() && runs[i].level >= level {
+                    i += 1;
+                }
+                runs[start..i].reverse();
+            }
+        }
+
+        runs
+    }
+}
+
+/// Runs of every paragraph of `text` in visual order, paragraph by paragraph.
+pub fn visual_runs(text: &str, base: Option<Direction>) -> Vec<BidiRun> {
+    let info = BidiInfo::new(text, base);
+    info.paragraphs.iter()
+        .flat_map(|paragraph| info.visual_runs(paragraph.range.clone()))
+        .collect()
+}
+
+#[derive(Copy, Clone)]
+struct Status {
+    level: u8,
+    override_class: Option<BidiClass>,
+    isolate: bool,
+}
+
+/// Resolves one paragraph, returning its base level and the level of each
+/// character.
+fn resolve_paragraph(chars: &[char], original: &[BidiClass], base: Option<Direction>) -> (u8, Vec<u8>) {
+    let n = chars.len();
+
+    // BD9: pair isolate initiators with their PDIs
+    let mut matching_pdi: Vec<Option<usize>> = vec![None; n];
+    let mut open = Vec::new();
+
+    for (i, &class) in original.iter().enumerate() {
+        if is_isolate_initiator(class) {
+            open.push(i);
+        } else if class == PDI
+            && let Some(initiator) = open.pop()
+        {
+            matching_pdi[initiator] = Some(i);
+        }
+    }
+
+    // P2/P3
+    let paragraph_level = match base {
+        Some(direction) => direction.is_rtl() as u8,
+        None => (first_strong(original, 0, n, &matching_pdi) == Some(R)) as u8,
+    };
+
+    // X1-X8: explicit embeddings, overrides and isolates
+    let mut classes = original.to_vec();
+    let mut levels = vec![paragraph_level; n];
+    let mut stack = vec![Status { level: paragraph_level, override_class: None, isolate: false }];
+    let mut overflow_isolates = 0;
+    let mut overflow_embeddings = 0;
+    let mut valid_isolates = 0;
+
+    for i in 0..n {
+        let top = stack[stack.len() - 1];
+
+        match original[i] {
+            RLE | LRE | RLO | LRO => {
+                levels[i] = top.level;
+
+                let level = next_level(top.level, matches!(original[i], RLE | RLO));
+                if level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+                    let override_class = match original[i] {
+                        RLO => Some(R),
+                        LRO => Some(L),
+                        _ => None,
+                    };
+                    stack.push(Status { level, override_class, isolate: false });
+                } else if overflow_isolates == 0 {
+                    overflow_embeddings += 1;
+                }
+            }
+            RLI | LRI | FSI => {
+                levels[i] = top.level;
+                if let Some(class) = top.override_class {
+                    classes[i] = class;
+                }
+
+                let rtl = match original[i] {
+                    RLI => true,
+                    LRI => false,
+                    _ => first_strong(original, i + 1, matching_pdi[i].unwrap_or(n), &matching_pdi) == Some(R),
+                };
+
+                let level = next_level(top.level, rtl);
+                if level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+                    valid_isolates += 1;
+                    stack.push(Status { level, override_class: None, isolate: true });
+                } else {
+                    overflow_isolates += 1;
+                }
+            }
+            PDI => {
+                if overflow_isolates > 0 {
+                    overflow_isolates -= 1;
+                } else if valid_isolates > 0 {
+                    overflow_embeddings = 0;
+                    while stack.last().is_some_and(|status| !status.isolate) {
+                        stack.pop();
+                    }
+                    stack.pop();
+                    valid_isolates -= 1;
+                }
+
+                let top = stack[stack.len() - 1];
+                levels[i] = top.level;
+                if let Some(class) = top.override_class {
+                    classes[i] = class;
+                }
+            }
+            PDF => {
+                levels[i] = top.level;
+                if overflow_isolates > 0 {
+                    // Matches an overflowed isolate's content, ignored
+                } else if overflow_embeddings > 0 {
+                    overflow_embeddings -= 1;
+                } else if !top.isolate && stack.len() >= 2 {
+                    stack.pop();
+                }
+            }
+            B => levels[i] = paragraph_level,
+            BN => levels[i] = top.level,
+            _ => {
+                levels[i] = top.level;
+                if let Some(class) = top.override_class {
+                    classes[i] = class;
+                }
+            }
+        }
+    }
+
+    // X9/X10: level runs over the characters that were not removed, linked
+    // across isolates into isolating run sequences
+    let mut runs: Vec<Vec<usize>> = Vec::new();
+    for i in (0..n).filter(|&i| !is_removed(original[i])) {
+        match runs.last_mut() {
+            Some(run) if levels[run[run.len() - 1]] == levels[i] => run.push(i),
+            _ => runs.push(vec![i]),
+        }
+    }
+
+    let mut run_starting_at = vec![None; n];
+    for (r, run) in runs.iter().enumerate() {
+        run_starting_at[run[0]] = Some(r);
+    }
+
+    let mut consumed = vec![false; runs.len()];
+    for r in 0..runs.len() {
+        if consumed[r] {
+            continue;
+        }
+
+        let mut sequence = runs[r].clone();
+        let mut last = sequence[sequence.len() - 1];
+
+        while is_isolate_initiator(original[last]) {
+            let Some(next) = matching_pdi[last].and_then(|pdi| run_starting_at[pdi]) else { break };
+            consumed[next] = true;
+            sequence.extend_from_slice(&runs[next]);
+            last = sequence[sequence.len() - 1];
+        }
+
+        resolve_sequence(&sequence, chars, original, &mut classes, &mut levels, paragraph_level, &matching_pdi);
+    }
+
+    // Removed characters take the level of the character before them
+    for i in 0..n {
+        if is_removed(original[i]) {
+            levels[i] = if i == 0 { paragraph_level } else { levels[i - 1] };
+        }
+    }
+
+    (paragraph_level, levels)
+}
+
+/// P2: the first strong type in `start..end`, skipping isolated content.
+fn first_strong(classes: &[BidiClass], start: usize, end: usize, matching_pdi: &[Option<usize>]) -> Option<BidiClass> {
+    let mut i = start;
+    while i < end {
+        match classes[i] {
+            L => return Some(L),
+            R | AL => return Some(R),
+            class if is_isolate_initiator(class) => match matching_pdi[i] {
+                Some(pdi) => i = pdi,
+                None => return None,
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Applies the weak (W1-W7), bracket (N0), neutral (N1-N2) and implicit
+/// (I1-I2) rules to one isolating run sequence.
+fn resolve_sequence(
+    sequence: &[usize],
+    chars: &[char],
+    original: &[BidiClass],
+    classes: &mut [BidiClass],
+    levels: &mut [u8],
+    paragraph_level: u8,
+    matching_pdi: &[Option<usize>],
+) {
+    let first = sequence[0];
+    let last = sequence[sequence.len() - 1];
+    let level = levels[first];
+
+    let before = (0..first).rev().find(|&i| !is_removed(original[i])).map_or(paragraph_level, |i| levels[i]);
+    let after = if is_isolate_initiator(original[last]) && matching_pdi[last].is_none() {
+        paragraph_level
+    } else {
+        (last + 1..original.len()).find(|&i| !is_removed(original[i])).map_or(paragraph_level, |i| levels[i])
+    };
+
+    let sos = level_direction(level.max(before));
+    let eos = level_direction(level.max(after));
+    let embedding = level_direction(level);
+
+    let mut types: Vec<BidiClass> = sequence.iter().map(|&i| classes[i]).collect();
+    let len = types.len();
+
+    // W1
+    for k in 0..len {
+        if types[k] == NSM {
+            types[k] = match k {
+                0 => sos,
+                _ if matches!(types[k - 1], LRI | RLI | FSI | PDI) => ON,
+                _ => types[k - 1],
+            };
+        }
+    }
+
+    // W2, W3
+    let mut last_strong = sos;
+    for t in types.iter_mut() {
+        match *t {
+            L | R | AL => last_strong = *t,
+            EN if last_strong == AL => *t = AN,
+            _ => {}
+        }
+    }
+    for t in types.iter_mut().filter(|t| **t == AL) {
+        *t = R;
+    }
+
+    // W4
+    for k in 1..len.saturating_sub(1) {
+        types[k] = match (types[k - 1], types[k], types[k + 1]) {
+            (EN, ES | CS, EN) => EN,
+            (AN, CS, AN) => AN,
+            (_, t, _) => t,
+        };
+    }
+
+    // W5
+    let mut k = 0;
+    while k < len {
+        if types[k] != ET {
+            k += 1;
+            continue;
+        }
+
+        let start = k;
+        while k < len && types[k] == ET {
+            k += 1;
+        }
+
+        if (start > 0 && types[start - 1] == EN) || (k < len && types[k] == EN) {
+            types[start..k].fill(EN);
+        }
+    }
+
+    // W6, W7
+    let mut last_strong = sos;
+    for t in types.iter_mut() {
+        match *t {
+            ES | ET | CS => *t = ON,
+            L | R => last_strong = *t,
+            EN if last_strong == L => *t = L,
+            _ => {}
+        }
+    }
+
+    // N0: paired brackets take the direction of their content
+    for (open, close) in bracket_pairs(sequence, chars, &types) {
+        let mut found_embedding = false;
+        let mut found_opposite = false;
+
+        for &t in &types[open + 1..close] {
+            match strong_direction(t) {
+                Some(d) if d == embedding => found_embedding = true,
+                Some(_) => found_opposite = true,
+                None => {}
+            }
+        }
+
+        let resolved = if found_embedding {
+            embedding
+        } else if found_opposite {
+            (0..open).rev().find_map(|k| strong_direction(types[k])).unwrap_or(sos)
+        } else {
+            continue;
+        };
+
+        for bracket in [open, close] {
+            types[bracket] = resolved;
+
+            // Marks on a bracket follow its new direction
+            for k in bracket + 1..len {
+                if original[sequence[k]] != NSM {
+                    break;
+                }
+                types[k] = resolved;
+            }
+        }
+    }
+
+    // N1, N2
+    let is_neutral = |t: BidiClass| matches!(t, B | S | WS | ON | LRI | RLI | FSI | PDI);
+    let mut k = 0;
+    while k < len {
+        if !is_neutral(types[k]) {
+            k += 1;
+            continue;
+        }
+
+        let start = k;
+        while k < len && is_neutral(types[k]) {
+            k += 1;
+        }
+
+        let before = if start == 0 { sos } else { strong_direction(types[start - 1]).unwrap_or(embedding) };
+        let after = if k == len { eos } else { strong_direction(types[k]).unwrap_or(embedding) };
+        types[start..k].fill(if before == after { before } else { embedding });
+    }
+
+    // I1, I2
+    for (k, &i) in sequence.iter().enumerate() {
+        classes[i] = types[k];
+        levels[i] = match (levels[i] % 2, types[k]) {
+            (0, R) => levels[i] + 1,
+            (0, AN | EN) => levels[i] + 2,
+            (1, L | EN | AN) => levels[i] + 1,
+            _ => levels[i],
+        };
+    }
+}
+
+/// BD16: positions within the sequence of matched bracket pairs, ordered
+/// by their opening bracket.
+fn bracket_pairs(sequence: &[usize], chars: &[char], types: &[BidiClass]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    let mut stack: Vec<(u32, usize)> = Vec::new();
+
+    for (k, &i) in sequence.iter().enumerate() {
+        if types[k] != ON {
+            continue;
+        }
+
+        match bracket(chars[i]) {
+            Some((closing, BracketType::Open)) => {
+                if stack.len() == MAX_BRACKET_DEPTH {
+                    break;
+                }
+                stack.push((closing, k));
+            }
+            Some((_, BracketType::Close)) => {
+                let cp = match chars[i] as u32 {
+                    0x232A => 0x3009,
+                    cp => cp,
+                };
+
+                if let Some(depth) = stack.iter().rposition(|&(closing, _)| closing == cp) {
+                    pairs.push((stack[depth].1, k));
+                    stack.truncate(depth);
+                }
+            }
+            None => {}
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::String;
+
+    /// A character of each class, standing in for it in the test text.
+    fn sample(class: BidiClass) -> char {
+        let c = match class {
+            L => 'a',
+            R => '\u{05D0}',
+            AL => '\u{0627}',
+            EN => '1',
+            ES => '+',
+            ET => '$',
+            AN => '\u{0661}',
+            CS => ',',
+            NSM => '\u{0300}',
+            BN => '\u{00AD}',
+            B => '\u{2029}',
+            S => '\t',
+            WS => ' ',
+            ON => '!',
+            LRE => '\u{202A}',
+            LRO => '\u{202D}',
+            RLE => '\u{202B}',
+            RLO => '\u{202E}',
+            PDF => '\u{202C}',
+            LRI => '\u{2066}',
+            RLI => '\u{2067}',
+            FSI => '\u{2068}',
+            PDI => '\u{2069}',
+        };
+        assert_eq!(bidi_class(c), class);
+        c
+    }
+
+    /// Checks one line in the manner of BidiTest.txt: the resolved level of
+    /// every character, `x` for those removed by X9, and the visual order of
+    /// the characters that are not removed.
+    fn check(classes: &[BidiClass], base: Option<Direction>, levels: &str, order: &str) {
+        let text: String = classes.iter().map(|&class| sample(class)).collect();
+        let info = BidiInfo::new(&text, base);
+        let starts: Vec<usize> = text.char_indices().map(|(offset, _)| offset).collect();
+
+        let resolved: Vec<String> = classes.iter().zip(&starts)
+            .map(|(&class, &offset)| if is_removed(class) { "x".into() } else { info.levels[offset].to_string() })
+            .collect();
+        assert_eq!(resolved.join(" "), levels, "levels of {classes:?}");
+
+        let mut visual = Vec::new();
+        for run in info.visual_runs(0..text.len()) {
+            let mut indices: Vec<usize> = (0..classes.len()).filter(|&k| run.range.contains(&starts[k])).collect();
+            if run.level % 2 == 1 {
+                indices.reverse();
+            }
+            visual.extend(indices.into_iter().filter(|&k| !is_removed(classes[k])).map(|k| k.to_string()));
+        }
+        assert_eq!(visual.join(" "), order, "order of {classes:?}");
+    }
+
+    #[test]
+    fn mixed_strong_and_number_runs() {
+        check(&[L, R, AN, EN], None, "0 1 2 2", "0 2 3 1");
+        check(&[R, L, EN], None, "1 2 2", "1 2 0");
+        // W2: European digits after Arabic letters become Arabic numbers
+        check(&[AL, EN], None, "1 2", "1 0");
+        // W4 and W5: separators and terminators inside numbers
+        check(&[R, EN, CS, EN, ET], Some(Direction::LeftToRight), "1 2 2 2 2", "1 2 3 4 0");
+    }
+
+    #[test]
+    fn explicit_embeddings_and_overrides() {
+        check(&[L, RLE, L, EN, PDF, L], None, "0 x 2 2 x 0", "0 2 3 5");
+        check(&[L, RLO, L, L, PDF, L], None, "0 x 1 1 x 0", "0 3 2 5");
+        check(&[R, LRE, R, PDF, R], None, "1 x 3 x 1", "4 2 0");
+    }
+
+    #[test]
+    fn isolates() {
+        check(&[L, RLI, L, PDI, L], None, "0 0 2 0 0", "0 1 2 3 4");
+        // FSI takes the direction of its first strong character
+        check(&[R, FSI, L, PDI, R], None, "1 1 2 1 1", "4 3 2 1 0");
+        // The paragraph direction skips isolated text
+        check(&[RLI, R, PDI, L], None, "0 1 0 0", "0 1 2 3");
+    }
+
+    #[test]
+    fn neutrals_between_runs() {
+        // N1: neutrals between strong characters of one direction take it
+        check(&[R, WS, R], Some(Direction::LeftToRight), "1 1 1", "2 1 0");
+        check(&[R, ON, EN], Some(Direction::LeftToRight), "1 1 2", "2 1 0");
+        // N2: otherwise they take the embedding direction
+        check(&[L, WS, R], Some(Direction::LeftToRight), "0 0 1", "0 1 2");
+        check(&[L, ON, R], Some(Direction::RightToLeft), "2 1 1", "2 1 0");
+    }
+}
@@ -9,12 +9,21 @@ use core::mem::size_of;
 use crate::Vec;
 use crate::Map;
 use crate::tables::cmap::CmapTable;
+use crate::tables::common::LayoutTable;
+use crate::tables::gdef::GdefTable;
 use crate::tables::glyf::Glyph;
 use crate::tables::head::HeadTable;
 use crate::tables::hhea::HheaTable;
 use crate::tables::hmtx::HmtxTable;
+use crate::tables::kern::KernTable;
 use crate::tables::loca::LocaTable;
 use crate::tables::maxp::MaxpTable;
+use crate::tables::name::NameTable;
+use crate::tables::os2::Os2Table;
+use crate::tables::post::PostTable;
+use crate::tables::vhea::VheaTable;
+use crate::tables::vmtx::VmtxTable;
+use crate::tables::vorg::VorgTable;
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct OffsetTable {
@@ -34,17 +43,6 @@ pub(crate) struct TableRecord {
     pub(crate) length: u32,
 }
 
-impl TableRecord {
-    pub(crate) fn new() -> TableRecord {
-        TableRecord {
-            table_tag: [0; 4],
-            check_sum: 0,
-            offset: 0,
-            length: 0,
-        }
-    }
-}
-
 
 pub struct TrueTypeFont {
     pub(crate) offset_table: OffsetTable,
@@ -53,13 +51,25 @@ pub struct TrueTypeFont {
     pub(crate) head: HeadTable,
     pub(crate) loca: LocaTable,
     pub(crate) maxp: MaxpTable,
-    pub(crate) glyf: TableRecord,
+    pub(crate) glyf_data: Vec<u8>,
     pub(crate) hhea: HheaTable,
     pub(crate) hmtx: HmtxTable,
-
-    pub(crate) glyph_data_table: Map<u32, Glyph>,
-    pub(crate) glyph_id_table: Map<char, u32>,
+    pub(crate) kern: KernTable,
+    /// Left empty: kerning pairs are looked up in the `kern` data itself
+    /// by `kerning_by_id`. Kept so code naming the field still compiles.
     pub kern_table: Map<(u32, u32), i16>,
+    pub(crate) gdef: GdefTable,
+    pub(crate) gsub: LayoutTable,
+    pub(crate) gpos: LayoutTable,
+    pub(crate) name: NameTable,
+    pub(crate) os2: Option<Os2Table>,
+    pub(crate) post: Option<PostTable>,
+    pub(crate) vhea: Option<VheaTable>,
+    pub(crate) vmtx: Option<VmtxTable>,
+    pub(crate) vorg: Option<VorgTable>,
+
+    /// Outlines parsed so far, filled in as glyphs are first used.
+    pub(crate) glyph_data_table: Map<u32, Glyph>,
 
     pub cache: crate::cache::Cache,
 
@@ -90,13 +100,22 @@ impl TrueTypeFont {
             head: HeadTable::new(),
             loca: LocaTable::Short(Vec::new()),
             maxp: MaxpTable::new(),
-            glyf: TableRecord::new(),
+            glyf_data: Vec::new(),
             hhea: HheaTable::new(),
             hmtx: HmtxTable::new(),
+            kern: KernTable::new(),
+            kern_table: Map::new(),
+            gdef: GdefTable::new(),
+            gsub: LayoutTable::new(),
+            gpos: LayoutTable::new(),
+            name: NameTable::new(),
+            os2: None,
+            post: None,
+            vhea: None,
+            vmtx: None,
+            vorg: None,
 
             glyph_data_table: Map::new(),
-            glyph_id_table: Map::new(),
-            kern_table: Map::new(),
 
             cache: crate::cache::Cache::new(),
 
@@ -151,11 +170,19 @@ impl TrueTypeFont {
         font.load_head(&font_bytes);
         font.load_maxp(&font_bytes);
         font.load_loca(&font_bytes);
-        font.load_glyf();
+        font.load_glyf(&font_bytes);
         font.load_hmtx(&font_bytes);
 
-        font.cache_all_glyphs(&font_bytes);
         font.load_kerning_pairs(&font_bytes);
+        font.load_gdef(&font_bytes);
+        font.load_gsub(&font_bytes);
+        font.load_gpos(&font_bytes);
+        font.load_name(&font_bytes);
+        font.load_os2(&font_bytes);
+        font.load_post(&font_bytes);
+        font.load_vhea(&font_bytes);
+        font.load_vmtx(&font_bytes);
+        font.load_vorg(&font_bytes);
 
         font
     }
@@ -0,0 +1,320 @@
+use crate::Vec;
+
+use super::tables::LINE_BREAK_CLASSES;
+
+/// The Unicode `Line_Break` property of a character, using the UAX #14
+/// abbreviations. Classes that UAX #14 resolves before pairing (AI, SG, XX,
+/// SA and CJ) are already mapped to the class they behave as.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineBreakClass {
+    BK,
+    CR,
+    LF,
+    CM,
+    NL,
+    WJ,
+    ZW,
+    GL,
+    SP,
+    ZWJ,
+    B2,
+    BA,
+    BB,
+    HY,
+    CB,
+    CL,
+    CP,
+    EX,
+    IN,
+    NS,
+    OP,
+    QU,
+    IS,
+    NU,
+    PO,
+    PR,
+    SY,
+    AK,
+    AL,
+    AP,
+    AS,
+    EB,
+    EM,
+    H2,
+    H3,
+    HL,
+    ID,
+    JL,
+    JV,
+    JT,
+    RI,
+    VF,
+    VI,
+}
+
+use LineBreakClass::*;
+
+/// The resolved line breaking class of a character.
+pub fn line_break_class(c: char) -> LineBreakClass {
+    let cp = c as u32;
+    match LINE_BREAK_CLASSES.binary_search_by(|&(start, end, _)| {
+        if cp < start {
+            core::cmp::Ordering::Greater
+        } else if cp > end {
+            core::cmp::Ordering::Less
+        } else {
+            core::cmp::Ordering::Equal
+        }
+    }) {
+        Ok(i) => LINE_BREAK_CLASSES[i].2,
+        Err(_) => AL,
+    }
+}
+
+/// A position where a line may, or must, be broken.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BreakOpportunity {
+    /// Byte offset of the first character of the next line.
+    pub offset: usize,
+    /// Whether the break is forced by a newline or the end of the text.
+    pub mandatory: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Break {
+    Mandatory,
+    Allowed,
+    Prohibited,
+}
+
+/// Aksara-like classes for LB28a. The dotted circle stands in for a missing
+/// base, so it pairs like an aksara too.
+fn is_aksara(class: LineBreakClass, c: char) -> bool {
+    matches!(class, AK | AS) || c == '\u{25CC}'
+}
+
+/// Quotation marks of general category Pi, which open a quotation.
+fn is_initial_quote(c: char) -> bool {
+    matches!(c, '\u{00AB}' | '\u{2018}' | '\u{201B}' | '\u{201C}' | '\u{201F}' | '\u{2039}'
+        | '\u{2E02}' | '\u{2E04}' | '\u{2E09}' | '\u{2E0C}' | '\u{2E1C}' | '\u{2E20}')
+}
+
+/// Quotation marks of general category Pf, which close a quotation.
+fn is_final_quote(c: char) -> bool {
+    matches!(c, '\u{00BB}' | '\u{2019}' | '\u{201D}' | '\u{203A}'
+        | '\u{2E03}' | '\u{2E05}' | '\u{2E0A}' | '\u{2E0D}' | '\u{2E1D}' | '\u{2E21}')
+}
+
+/// Line break opportunities in `text` following the Unicode Line Breaking
+/// Algorithm (UAX #14), in logical order. The end of the text is always a
+/// mandatory break.
+pub fn line_breaks(text: &str) -> Vec<BreakOpportunity> {
+    let chars: Vec<(usize, char, LineBreakClass)> = text.char_indices()
+        .map(|(offset, c)| (offset, c, line_break_class(c)))
+        .collect();
+
+    let mut breaks = Vec::new();
+    let Some(&(_, first_char, first)) = chars.first() else { return breaks };
+
+    // LB10: a mark or joiner with nothing to attach to is alphabetic
+    let mut a = if matches!(first, CM | ZWJ) { AL } else { first };
+    let mut a_char = first_char;
+    let mut before_a: Option<LineBreakClass> = None;
+    let mut before_spaces = a;
+    // LB15a: whether `before_spaces` opens a quotation at the start of a
+    // line, after a space or after other opening punctuation
+    let mut opening_quote = a == QU && is_initial_quote(first_char);
+    let mut regional_indicators = usize::from(a == RI);
+
+    for i in 1..chars.len() {
+        let (offset, c, class) = chars[i];
+        let raw = chars[i - 1].2;
+        let b = if matches!(class, CM | ZWJ) { AL } else { class };
+        let next = chars.get(i + 1).map(|&(_, c, class)| (c, class));
+
+        let action = if raw == BK {
+            Break::Mandatory
+        } else if raw == CR && b == LF {
+            Break::Prohibited
+        } else if matches!(raw, CR | LF | NL) {
+            Break::Mandatory
+        } else if matches!(class, BK | CR | LF | NL | SP | ZW) {
+            Break::Prohibited
+        } else if before_spaces == ZW {
+            Break::Allowed
+        } else if raw == ZWJ {
+            Break::Prohibited
+        } else if matches!(class, CM | ZWJ) && !matches!(a, SP | ZW) {
+            // LB9: marks and joiners take the class of their base
+            continue;
+        } else {
+            pair(a, a_char, before_a, before_spaces, opening_quote, regional_indicators, b, c, next)
+        };
+
+        match action {
+            Break::Mandatory => breaks.push(BreakOpportunity { offset, mandatory: true }),
+            Break::Allowed => breaks.push(BreakOpportunity { offset, mandatory: false }),
+            Break::Prohibited => {}
+        }
+
+        regional_indicators = if b == RI { regional_indicators + 1 } else { 0 };
+        before_a = Some(a);
+        a = b;
+        a_char = c;
+        if b != SP {
+            opening_quote = b == QU && is_initial_quote(c) && matches!(before_a, Some(BK | CR | LF | NL | OP | QU | GL | SP | ZW));
+            before_spaces = b;
+        }
+    }
+
+    if breaks.last().is_none_or(|last| last.offset < text.len()) {
+        breaks.push(BreakOpportunity { offset: text.len(), mandatory: true });
+    }
+    breaks
+}
+
+/// Rules LB11 to LB31 for the pair `a` `b`. `before_spaces` is the last
+/// class before any spaces ending at `a`, `opening_quote` whether that
+/// class is an opening quotation mark for LB15a, and `regional_indicators`
+/// the length of the run of regional indicators ending at `a`.
+#[allow(clippy::too_many_arguments)]
+fn pair(
+    a: LineBreakClass,
+    a_char: char,
+    before_a: Option<LineBreakClass>,
+    before_spaces: LineBreakClass,
+    opening_quote: bool,
+    regional_indicators: usize,
+    b: LineBreakClass,
+    b_char: char,
+    next: Option<(char, LineBreakClass)>,
+) -> Break {
+    let alphabetic = |class| matches!(class, AL | HL);
+    let hangul = |class| matches!(class, JL | JV | JT | H2 | H3);
+
+    let prohibited = match (a, b) {
+        // LB11, LB12, LB12a
+        (WJ, _) | (_, WJ) | (GL, _) => true,
+        (_, GL) if !matches!(a, SP | BA | HY) => true,
+        // LB13, LB14
+        (_, CL | CP | EX | SY) => true,
+        _ if before_spaces == OP => true,
+        // LB15a, LB15b: quotation marks stay with the text they open or close
+        _ if opening_quote => true,
+        (_, QU) if is_final_quote(b_char)
+            && next.is_none_or(|(_, class)| matches!(class, SP | GL | WJ | CL | QU | CP | EX | IS | SY | BK | CR | LF | NL | ZW)) => true,
+        // LB15c, LB15d
+        (SP, IS) if next.is_some_and(|(_, class)| class == NU) => false,
+        (_, IS) => true,
+        // LB16, LB17
+        (_, NS) if matches!(before_spaces, CL | CP) => true,
+        (_, B2) if before_spaces == B2 => true,
+        // LB18
+        (SP, _) => false,
+        // LB19, LB19a: without East Asian Width data every quotation mark
+        // counts as non-East Asian, so none allow a break on either side
+        (QU, _) | (_, QU) => true,
+        // LB20
+        (CB, _) | (_, CB) => false,
+        // LB20a: a word-initial hyphen stays with the word
+        (HY, _) if alphabetic(b) && before_a.is_none_or(|class| matches!(class, BK | CR | LF | NL | SP | ZW | CB | GL)) => true,
+        // LB21, LB21a, LB21b
+        (_, BA | HY | NS) | (BB, _) => true,
+        (HY | BA, _) if before_a == Some(HL) && b != HL => true,
+        (SY, HL) => true,
+        // LB22
+        (_, IN) => true,
+        // LB23, LB23a, LB24
+        (AL | HL, NU) | (NU, AL | HL) => true,
+        (PR, ID | EB | EM) | (ID | EB | EM, PO) => true,
+        (PR | PO, AL | HL) | (AL | HL, PR | PO) => true,
+        // LB25: numbers with their prefixes, suffixes and separators
+        (CL | CP | NU, PO | PR) | (PO | PR, OP | NU) | (HY | IS | NU | SY, NU) => true,
+        // LB26, LB27: Korean syllable blocks
+        (JL, JL | JV | H2 | H3) | (JV | H2, JV | JT) | (JT | H3, JT) => true,
+        _ if hangul(a) && b == PO || a == PR && hangul(b) => true,
+        // LB28
+        _ if alphabetic(a) && alphabetic(b) => true,
+        // LB28a: Brahmic orthographic syllables
+        (AP, _) if is_aksara(b, b_char) => true,
+        (_, VF | VI) if is_aksara(a, a_char) => true,
+        (VI, _) if matches!(before_a, Some(AK | AS)) && (b == AK || b_char == '\u{25CC}') => true,
+        _ if is_aksara(a, a_char) && is_aksara(b, b_char) && next.is_some_and(|(_, class)| class == VF) => true,
+        // LB29, LB30
+        (IS, AL | HL) => true,
+        (AL | HL | NU, OP) | (CP, AL | HL | NU) => true,
+        // LB30a: regional indicators pair into flags
+        (RI, RI) => regional_indicators % 2 == 1,
+        // LB30b
+        (EB, EM) => true,
+        // LB31
+        _ => false,
+    };
+
+    if prohibited { Break::Prohibited } else { Break::Allowed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits `text` at every break opportunity.
+    fn segments(text: &str) -> Vec<&str> {
+        let mut start = 0;
+        line_breaks(text).into_iter()
+            .map(|opportunity| {
+                let segment = &text[start..opportunity.offset];
+                start = opportunity.offset;
+                segment
+            })
+            .collect()
+    }
+
+    #[test]
+    fn breaks_after_spaces() {
+        assert_eq!(segments("hello world"), ["hello ", "world"]);
+        assert_eq!(segments("a  b"), ["a  ", "b"]);
+        assert_eq!(segments("a\u{00A0}b c"), ["a\u{00A0}b ", "c"]);
+        assert_eq!(segments("a\u{200B}b"), ["a\u{200B}", "b"]);
+    }
+
+    #[test]
+    fn newlines_are_mandatory_breaks() {
+        let breaks = line_breaks("a b\r\nc");
+        let offsets: Vec<(usize, bool)> = breaks.iter().map(|b| (b.offset, b.mandatory)).collect();
+        assert_eq!(offsets, [(2, false), (5, true), (6, true)]);
+    }
+
+    #[test]
+    fn breaks_after_hyphens() {
+        assert_eq!(segments("well-known"), ["well-", "known"]);
+        // LB20a: a hyphen starting a word stays with it
+        assert_eq!(segments("a -b"), ["a ", "-b"]);
+        // LB25: a minus sign stays with its number
+        assert_eq!(segments("x -5"), ["x ", "-5"]);
+    }
+
+    #[test]
+    fn breaks_between_ideographs() {
+        assert_eq!(segments("中文字"), ["中", "文", "字"]);
+        // Closing punctuation and small kana do not start a line
+        assert_eq!(segments("中。文"), ["中。", "文"]);
+        assert_eq!(segments("あぁい"), ["あぁ", "い"]);
+    }
+
+    #[test]
+    fn keeps_numbers_together() {
+        assert_eq!(segments("$1,234.56 x"), ["$1,234.56 ", "x"]);
+        assert_eq!(segments("(12%) y"), ["(12%) ", "y"]);
+        // LB15c: a separator after a space starts a number
+        assert_eq!(segments("a .5"), ["a ", ".5"]);
+    }
+
+    #[test]
+    fn quotation_marks_stay_with_their_text() {
+        // LB15a and LB15b hold across the spaces inside the quotation
+        assert_eq!(segments("say « hi »"), ["say ", "« hi »"]);
+        assert_eq!(segments("\u{201C} a \u{201D} b"), ["\u{201C} a \u{201D} ", "b"]);
+    }
+}
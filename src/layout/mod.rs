@@ -0,0 +1,449 @@
+mod line_break;
+mod tables;
+
+pub use line_break::{line_break_class, line_breaks, BreakOpportunity, LineBreakClass};
+
+use core::ops::Range;
+
+use crate::bidi::BidiInfo;
+use crate::font::TrueTypeFont;
+use crate::render::Bounds;
+use crate::script::{resolve_scripts, Script};
+use crate::shaping::{ShapedGlyph, ShapingOptions};
+use crate::vec;
+use crate::Vec;
+
+/// Horizontal placement of each line within the layout width.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Stretches the spaces of every line that ends in a soft break to fill
+    /// the maximum width. Lines ending a paragraph stay left aligned.
+    Justify,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LayoutOptions {
+    pub alignment: Alignment,
+    /// Distance between tab stops in pixels; `None` uses four space widths.
+    pub tab_width: Option<f32>,
+    /// Script, language, direction and features used to shape each line.
+    /// [`Direction::TopToBottom`] sets the text in vertical columns running
+    /// right to left.
+    pub shaping: ShapingOptions,
+}
+
+/// A glyph placed in the layout. `x` and `y` are in pixels from the top left
+/// of the layout, with `y` growing downwards, and already include the glyph
+/// offsets from shaping.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PositionedGlyph {
+    pub glyph_id: u32,
+    /// Byte offset of the first character the glyph was shaped from.
+    pub cluster: usize,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A line of text. In vertical layouts the line is a column: `x` and
+/// `width` then run down the column and `baseline` is the x coordinate of
+/// its center line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    /// Byte range in the source text, including trailing whitespace and the
+    /// newline that ends the line.
+    pub range: Range<usize>,
+    /// Glyphs in visual order. Trailing whitespace and tabs have no glyph.
+    pub glyphs: Vec<PositionedGlyph>,
+    /// Left edge of the line after alignment.
+    pub x: f32,
+    /// Width of the line without its trailing whitespace.
+    pub width: f32,
+    pub baseline: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    pub lines: Vec<Line>,
+    /// The maximum width when one was given, otherwise the widest line.
+    pub width: f32,
+    pub height: f32,
+    /// Distance between consecutive baselines, from the line spacing of
+    /// [`TrueTypeFont::line_metrics`], or from `vhea` between vertical columns.
+    pub line_height: f32,
+}
+
+/// Size of a single line of text, in pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextMeasurement {
+    pub advance_width: f32,
+    /// Distance from the baseline up to the [`TrueTypeFont::line_metrics`]
+    /// ascender.
+    pub ascent: f32,
+    /// Distance from the baseline down to the descender, positive.
+    pub descent: f32,
+    pub line_gap: f32,
+    /// Tight box around the glyph outlines, relative to the pen origin on the
+    /// baseline with `y` growing downwards. `None` when nothing has ink.
+    pub ink_bounds: Option<Bounds>,
+}
+
+/// Pixel advances of every character of the text, used to find where lines
+/// must break before the lines themselves are shaped.
+struct Measure<'a> {
+    text: &'a str,
+    /// Advance of the glyphs clustered at each byte; zero for bytes that do
+    /// not start a cluster.
+    advances: Vec<f32>,
+    tab_width: f32,
+}
+
+/// The running width of a line being filled.
+struct Pen {
+    /// Byte offset the line has been measured up to.
+    end: usize,
+    /// Pen position at `end`, trailing whitespace included.
+    x: f32,
+    /// Pen position after the last character that is not whitespace.
+    content: f32,
+}
+
+impl Pen {
+    fn at(start: usize) -> Self {
+        Pen { end: start, x: 0.0, content: 0.0 }
+    }
+}
+
+impl Measure<'_> {
+    fn next_tab_stop(&self, x: f32) -> f32 {
+        if self.tab_width > 0.0 { ((x / self.tab_width) as u32 + 1) as f32 * self.tab_width } else { x }
+    }
+
+    /// Width of the line measured by `pen` once it reaches `end`, without
+    /// trailing whitespace. Only the text past the pen's last offset is
+    /// measured, so a line grows in time linear in its length.
+    fn width_to(&self, pen: &mut Pen, end: usize) -> f32 {
+        for (offset, c) in self.text[pen.end..end].char_indices() {
+            pen.x = if c == '\t' { self.next_tab_stop(pen.x) } else { pen.x + self.advances[pen.end + offset] };
+            if !c.is_whitespace() {
+                pen.content = pen.x;
+            }
+        }
+        pen.end = end;
+
+        pen.content
+    }
+
+    /// The furthest character boundary in `range` at which the text up to
+    /// it still fits `max_width`, for words wider than a whole line. At
+    /// least one character is kept, and marks stay with their base.
+    fn split(&self, range: Range<usize>, max_width: f32) -> usize {
+        let mut split = None;
+        let mut pen = Pen::at(range.start);
+
+        for (offset, c) in self.text[range.clone()].char_indices().skip(1) {
+            if matches!(line_break_class(c), LineBreakClass::CM | LineBreakClass::ZWJ) {
+                continue;
+            }
+
+            let end = range.start + offset;
+            if split.is_some() && self.width_to(&mut pen, end) > max_width {
+                break;
+            }
+            split = Some(end);
+        }
+
+        split.unwrap_or(range.end)
+    }
+}
+
+impl TrueTypeFont {
+    /// Measures `text` as one line from the `hmtx` advances, the glyph
+    /// bounding boxes and the kerning of its first script, without glyph
+    /// substitution or rasterizing.
+    pub fn measure_text(&self, text: &str, size: usize) -> TextMeasurement {
+        let scale = self.scale_for_size(size);
+
+        let script = text.chars().map(Script::of).find(|script| script.is_explicit()).unwrap_or(Script::COMMON);
+        let options = ShapingOptions { script: script.opentype_tag(), ..Default::default() };
+
+        let mut buffer = self.glyph_buffer(text);
+        self.position_kerning(&mut buffer, &options);
+        self.hide_default_ignorables(&mut buffer, text);
+
+        let mut x = 0.0;
+        let mut ink: Option<Bounds> = None;
+
+        for (info, position) in buffer.glyphs.iter().zip(&buffer.positions) {
+            if let Some((x_min, y_min, x_max, y_max)) = self.glyph_bounds(info.glyph_id) {
+                let left = x + position.x_offset as f32 * scale;
+                let bounds = Bounds {
+                    x_min: left + x_min as f32 * scale,
+                    y_min: -(y_max as f32 + position.y_offset as f32) * scale,
+                    x_max: left + x_max as f32 * scale,
+                    y_max: -(y_min as f32 + position.y_offset as f32) * scale,
+                };
+                ink = Some(ink.map_or(bounds, |ink| ink.union(&bounds)));
+            }
+
+            x += position.x_advance as f32 * scale;
+        }
+
+        let metrics = self.line_metrics(size);
+
+        TextMeasurement {
+            advance_width: x,
+            ascent: metrics.ascender,
+            descent: -metrics.descender,
+            line_gap: metrics.line_gap,
+            ink_bounds: ink,
+        }
+    }
+
+    /// Lays `text` out in lines of positioned glyphs at `size`. Lines break
+    /// at newlines and, when `max_width` is given, at the last line break
+    /// opportunity (UAX #14) that keeps them within `max_width` pixels.
+    /// Words wider than a whole line are broken between characters. In
+    /// vertical layouts `max_width` limits the height of each column.
+    pub fn layout(&self, text: &str, size: usize, max_width: Option<f32>, options: &LayoutOptions) -> Layout {
+        let scale = self.scale_for_size(size);
+        let info = BidiInfo::new(text, None);
+        let scripts = resolve_scripts(text);
+        let vertical = options.shaping.direction.is_vertical();
+
+        // Pen movement along the line, in pixels
+        let advance = |glyph: &ShapedGlyph| {
+            if vertical { -glyph.y_advance as f32 * scale } else { glyph.x_advance as f32 * scale }
+        };
+
+        let mut advances = vec![0.0; text.len()];
+        for paragraph in &info.paragraphs {
+            for glyph in self.shape_line(text, &info, &scripts, paragraph.range.clone(), &options.shaping) {
+                advances[glyph.cluster] += advance(&glyph);
+            }
+        }
+
+        let space = self.get_glyph_id(' ');
+        let space_width = if vertical {
+            self.vertical_metrics(space).advance_height as f32 * scale
+        } else {
+            self.h_metric(space).0 as f32 * scale
+        };
+        let measure = Measure { text, advances, tab_width: options.tab_width.unwrap_or(4.0 * space_width) };
+
+        // Byte ranges of the lines, and whether each ends its paragraph
+        let mut ranges: Vec<(Range<usize>, bool)> = Vec::new();
+        let mut start = 0;
+        let mut pen = Pen::at(start);
+        let mut fit = None;
+        let breaks = line_breaks(text);
+        let mut i = 0;
+
+        while i < breaks.len() {
+            let opportunity = breaks[i];
+
+            if let Some(max_width) = max_width
+                && measure.width_to(&mut pen, opportunity.offset) > max_width
+            {
+                if let Some(end) = fit.take() {
+                    ranges.push((start..end, false));
+                    start = end;
+                    pen = Pen::at(start);
+                    continue;
+                }
+
+                let end = measure.split(start..opportunity.offset, max_width);
+                if end < opportunity.offset {
+                    ranges.push((start..end, false));
+                    start = end;
+                    pen = Pen::at(start);
+                    continue;
+                }
+            }
+
+            if opportunity.mandatory {
+                ranges.push((start..opportunity.offset, true));
+                start = opportunity.offset;
+                pen = Pen::at(start);
+                fit = None;
+            } else {
+                fit = Some(opportunity.offset);
+            }
+            i += 1;
+        }
+
+        let (ascent, descent, line_gap) = match (vertical, self.vhea) {
+            (true, Some(vhea)) => (vhea.ascender as f32 * scale, -(vhea.descender as f32) * scale, vhea.line_gap as f32 * scale),
+            // Columns one em box wide, centered on the glyph origins
+            (true, None) => {
+                let half = ((self.hhea.ascender as i32 - self.hhea.descender as i32) as f32 / 2.0) as i16;
+                (half as f32 * scale, half as f32 * scale, self.hhea.line_gap as f32 * scale)
+            }
+            (false, _) => {
+                let metrics = self.line_metrics(size);
+                (metrics.ascender, -metrics.descender, metrics.line_gap)
+            }
+        };
+        let line_height = ascent + descent + line_gap;
+
+        // Vertical columns run right to left from the right edge
+        let columns_width = ranges.len() as f32 * line_height - line_gap;
+
+        let mut lines = Vec::new();
+        for (n, (range, ends_paragraph)) in ranges.into_iter().enumerate() {
+            let baseline = if vertical {
+                columns_width - ascent - n as f32 * line_height
+            } else {
+                ascent + n as f32 * line_height
+            };
+            let content_end = range.start + text[range.clone()].trim_end().len();
+
+            let mut glyphs = Vec::new();
+            let mut x = 0.0;
+
+            for paragraph in &info.paragraphs {
+                let part = paragraph.range.start.max(range.start)..paragraph.range.end.min(range.end);
+                if part.is_empty() {
+                    continue;
+                }
+
+                for glyph in self.shape_line(text, &info, &scripts, part, &options.shaping) {
+                    if glyph.cluster >= content_end {
+                        continue;
+                    }
+
+                    if text[glyph.cluster..].starts_with('\t') {
+                        x = measure.next_tab_stop(x);
+                        continue;
+                    }
+
+                    let (glyph_x, glyph_y) = if vertical {
+                        (baseline + glyph.x_offset as f32 * scale, x - glyph.y_offset as f32 * scale)
+                    } else {
+                        (x + glyph.x_offset as f32 * scale, baseline - glyph.y_offset as f32 * scale)
+                    };
+
+                    glyphs.push(PositionedGlyph {
+                        glyph_id: glyph.glyph_id,
+                        cluster: glyph.cluster,
+                        x: glyph_x,
+                        y: glyph_y,
+                    });
+                    x += advance(&glyph);
+                }
+            }
+
+            let mut line = Line { range, glyphs, x: 0.0, width: x, baseline };
+
+            if options.alignment == Alignment::Justify
+                && !ends_paragraph
+                && let Some(max_width) = max_width
+            {
+                justify(&mut line, text, max_width, vertical);
+            }
+
+            lines.push(line);
+        }
+
+        let width = max_width.unwrap_or_else(|| lines.iter().map(|line| line.width).fold(0.0, f32::max));
+
+        for line in &mut lines {
+            line.x = match options.alignment {
+                Alignment::Left | Alignment::Justify => 0.0,
+                Alignment::Center => (width - line.width) / 2.0,
+                Alignment::Right => width - line.width,
+            };
+
+            for glyph in &mut line.glyphs {
+                if vertical { glyph.y += line.x } else { glyph.x += line.x }
+            }
+        }
+
+        let columns_width = if lines.is_empty() { 0.0 } else { columns_width };
+        if vertical {
+            return Layout { lines, width: columns_width, height: width, line_height };
+        }
+
+        let height = if lines.is_empty() { 0.0 } else { lines.len() as f32 * line_height - line_gap };
+
+        Layout { lines, width, height, line_height }
+    }
+}
+
+/// Spreads the space between `line.width` and `max_width` evenly over the
+/// spaces of the line, down the column when `vertical`.
+fn justify(line: &mut Line, text: &str, max_width: f32, vertical: bool) {
+    let is_space = |glyph: &PositionedGlyph| text[glyph.cluster..].chars().next().is_some_and(char::is_whitespace);
+
+    let spaces = line.glyphs.iter().filter(|glyph| is_space(glyph)).count();
+    if spaces == 0 || line.width >= max_width {
+        return;
+    }
+
+    let extra = (max_width - line.width) / spaces as f32;
+    let mut shift = 0.0;
+
+    for glyph in &mut line.glyphs {
+        if vertical { glyph.y += shift } else { glyph.x += shift }
+        if is_space(glyph) {
+            shift += extra;
+        }
+    }
+
+    line.width = max_width;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measures `text` with every character ten pixels wide, except marks,
+    /// and tab stops every forty pixels.
+    fn measure(text: &str) -> Measure<'_> {
+        let mut advances = vec![0.0; text.len()];
+        for (offset, c) in text.char_indices() {
+            if line_break_class(c) != LineBreakClass::CM {
+                advances[offset] = 10.0;
+            }
+        }
+        Measure { text, advances, tab_width: 40.0 }
+    }
+
+    #[test]
+    fn running_width_matches_a_fresh_measurement() {
+        let measure = measure("ab cd\tef gh");
+        let mut pen = Pen::at(0);
+
+        for end in [2, 3, 5, 6, 8, 11] {
+            let running = measure.width_to(&mut pen, end);
+            assert_eq!(running, measure.width_to(&mut Pen::at(0), end), "width up to {end}");
+        }
+        assert_eq!(measure.width_to(&mut Pen::at(0), 8), 100.0);
+    }
+
+    #[test]
+    fn width_leaves_out_trailing_whitespace() {
+        let measure = measure("ab  \t");
+        assert_eq!(measure.width_to(&mut Pen::at(0), 5), 20.0);
+        assert_eq!(measure.width_to(&mut Pen::at(1), 5), 10.0);
+    }
+
+    #[test]
+    fn words_wider_than_the_line_are_split() {
+        let measure = measure("abcdef");
+        assert_eq!(measure.split(0..6, 35.0), 3);
+        assert_eq!(measure.split(2..6, 25.0), 4);
+        // At least one character is kept on the line
+        assert_eq!(measure.split(0..6, 5.0), 1);
+    }
+
+    #[test]
+    fn split_keeps_marks_with_their_base() {
+        let measure = measure("ae\u{0301}\u{0301}b");
+        assert_eq!(measure.split(0..measure.text.len(), 15.0), 1);
+        assert_eq!(measure.split(0..measure.text.len(), 25.0), 6);
+    }
+}
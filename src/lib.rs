@@ -39,6 +39,13 @@ use alloc::vec::Vec;
 use std::vec::Vec;
 
 
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+#[cfg(feature = "std")]
+use std::string::String;
+
+
 #[cfg(not(feature = "std"))]
 use alloc::vec;
 
@@ -61,6 +68,18 @@ pub mod cache;
 /// Font table structures (CMAP, GLYF, etc.)
 pub mod tables;
 
+/// Glyph buffers and OpenType feature application
+pub mod shaping;
+
+/// Unicode Bidirectional Algorithm (UAX #9)
+pub mod bidi;
+
+/// Script property lookup and text itemization
+pub mod script;
+
+/// Line breaking (UAX #14) and paragraph layout
+pub mod layout;
+
 
 
 pub use crate::font::TrueTypeFont;
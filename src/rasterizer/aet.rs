@@ -30,6 +30,10 @@ pub(crate) fn rasterize(
             continue;
         }
 
+        let (shift_x, shift_y) = contour.grid_shift(scale);
+        let x_offset = x_offset - shift_x;
+        let scale_y_max = scale_y_max - shift_y;
+
         let mut i = 0;
         while i < num_points {
             let current = &contour.points[i];
@@ -2,16 +2,37 @@ use crate::tables::glyf::{
     CompositeComponent,
     Glyph,
     ProtoGlyph,
+    SimpleGlyph,
     ARGS_ARE_XY_VALUES,
+    ROUND_XY_TO_GRID,
+    SCALED_COMPONENT_OFFSET,
+    UNSCALED_COMPONENT_OFFSET,
     WE_HAVE_AN_X_AND_Y_SCALE,
     WE_HAVE_A_SCALE,
     WE_HAVE_A_TWO_BY_TWO
 };
 
-use crate::Vec;
+use crate::{Vec, vec};
 use crate::F32NoStd;
 use crate::font::TrueTypeFont;
 
+/// How deep composites may nest. `maxp` has a field for this, but fonts
+/// often leave it at zero or understate it, so a fixed cap is used instead.
+const MAX_COMPONENT_DEPTH: usize = 16;
+
+/// How many components one glyph may place in total, nested ones included.
+/// Without it, composites that each repeat the next several times grow
+/// exponentially within the depth limit.
+const MAX_COMPONENTS: usize = 1024;
+
+/// The state of assembling one composite glyph.
+pub(crate) struct ComponentWalk {
+    /// The composites being assembled, outermost first.
+    path: Vec<u32>,
+    /// Components that may still be placed.
+    remaining: usize,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Point {
     pub(crate) x: i16,
@@ -22,56 +43,40 @@ pub(crate) struct Point {
 #[derive(Debug, Clone)]
 pub(crate) struct Contour {
     pub(crate) points: Vec<Point>,
+    /// Offset in font units from components flagged `ROUND_XY_TO_GRID`.
+    /// The rasterizer moves the contour so this offset lands on whole pixels.
+    pub(crate) grid_offset: (f32, f32),
 }
 
 impl Contour {
     pub(crate) fn new(size: usize) -> Self {
-        Contour { points: Vec::with_capacity(size) }
+        Contour { points: Vec::with_capacity(size), grid_offset: (0.0, 0.0) }
+    }
+
+    /// The shift in pixels that rounds `grid_offset` to the pixel grid at
+    /// `scale`, with y pointing up like the outline.
+    pub(crate) fn grid_shift(&self, scale: f32) -> (f32, f32) {
+        let (x, y) = (self.grid_offset.0 * scale, self.grid_offset.1 * scale);
+        (x.round() - x, y.round() - y)
     }
 }
 
 impl TrueTypeFont {
-    pub(crate) fn load_points(&self, glyph: &mut ProtoGlyph, font: &TrueTypeFont, font_bytes: &[u8]) -> Glyph {
+    pub(crate) fn load_points(&self, glyph_id: u32, glyph: &mut ProtoGlyph) -> Glyph {
         match glyph {
             ProtoGlyph::Simple(g) => {
-                let num_points = g.end_pts_of_contours.last().map(|&e| (e + 1) as usize).unwrap_or(0);
-                let expanded_flags = expand_flags(&g.flags, num_points);
-
-                g.points.reserve(g.end_pts_of_contours.len());
-
-                let mut contour_start = 0;
-                for i in 0..g.end_pts_of_contours.len() {
-                    let contour_size = if i == 0 {
-                        g.end_pts_of_contours[i] as usize + 1
-                    } else {
-                        (g.end_pts_of_contours[i] - g.end_pts_of_contours[i - 1]) as usize
-                    };
-                    // Add +1 to capacity for the closing point
-                    let mut contour = Contour::new(contour_size + 1);
-
-                    for j in contour_start..=g.end_pts_of_contours[i] as usize {
-                        contour.points.push(Point {
-                            x: g.x_coordinates[j],
-                            y: g.y_coordinates[j],
-                            on_curve: (expanded_flags[j] & 0x01) != 0,
-                        });
-                    }
-
-                    // Append the first point as the last point to close the contour
-                    if !contour.points.is_empty() {
-                        let first_point = contour.points[0];
-                        contour.points.push(first_point);
-                    }
-
-                    contour_start = g.end_pts_of_contours[i] as usize + 1;
-                    g.points.push(contour);
-                }
-
+                g.points = simple_contours(g);
                 insert_midpoints(&mut g.points);
             }
 
             ProtoGlyph::Composite(g) => {
-                load_from_parent(&mut g.points, &g.components, font, font_bytes);
+                // A composite that nests too deep, places too many components
+                // or contains itself has no outline rather than exhausting
+                // the stack or hanging
+                let mut walk = ComponentWalk { path: vec![glyph_id], remaining: MAX_COMPONENTS };
+                if !load_from_parent(&mut g.points, &g.components, self, &mut walk) {
+                    g.points.clear();
+                }
                 insert_midpoints(&mut g.points);
             }
 
@@ -82,54 +87,95 @@ impl TrueTypeFont {
     }
 }
 
-pub(crate) fn load_from_parent(master: &mut Vec<Contour>, comps: &Vec<CompositeComponent>, font: &TrueTypeFont, font_bytes: &[u8]) {
+/// The contours of a simple glyph, each closed by repeating its first point.
+fn simple_contours(g: &SimpleGlyph) -> Vec<Contour> {
+    let num_points = g.end_pts_of_contours.last().map(|&e| (e + 1) as usize).unwrap_or(0);
+    let expanded_flags = expand_flags(&g.flags, num_points);
+
+    let mut contours = Vec::with_capacity(g.end_pts_of_contours.len());
+
+    let mut contour_start = 0;
+    for i in 0..g.end_pts_of_contours.len() {
+        let contour_size = if i == 0 {
+            g.end_pts_of_contours[i] as usize + 1
+        } else {
+            (g.end_pts_of_contours[i] - g.end_pts_of_contours[i - 1]) as usize
+        };
+        // Add +1 to capacity for the closing point
+        let mut contour = Contour::new(contour_size + 1);
+
+        for j in contour_start..=g.end_pts_of_contours[i] as usize {
+            contour.points.push(Point {
+                x: g.x_coordinates[j],
+                y: g.y_coordinates[j],
+                on_curve: (expanded_flags[j] & 0x01) != 0,
+            });
+        }
+
+        // Append the first point as the last point to close the contour
+        if !contour.points.is_empty() {
+            let first_point = contour.points[0];
+            contour.points.push(first_point);
+        }
+
+        contour_start = g.end_pts_of_contours[i] as usize + 1;
+        contours.push(contour);
+    }
+
+    contours
+}
+
+/// Appends the contours of every component to `master`. Each component is
+/// assembled in its own coordinates first, nested composites included, and
+/// then placed with its transform and offset.
+///
+/// Returns false when a component refers back to a composite on the walk's
+/// path, the nesting goes past `MAX_COMPONENT_DEPTH` or the glyph places
+/// more than `MAX_COMPONENTS` components.
+pub(crate) fn load_from_parent(master: &mut Vec<Contour>, comps: &[CompositeComponent], font: &TrueTypeFont, walk: &mut ComponentWalk) -> bool {
     for component in comps.iter() {
-        let real_glyph = &mut font.get_glyph(font_bytes, component.glyph_index as u32);
+        let glyph_id = component.glyph_index as u32;
 
-        match real_glyph {
-            ProtoGlyph::Simple(g) => {
-                let num_points = g.end_pts_of_contours.last().map(|&e| (e + 1) as usize).unwrap_or(0);
-                let expanded_flags = expand_flags(&g.flags, num_points);
-
-                g.points.reserve(g.end_pts_of_contours.len());
-
-                let mut contour_start = 0;
-                for i in 0..g.end_pts_of_contours.len() {
-                    let contour_size = if i == 0 {
-                        g.end_pts_of_contours[i] as usize + 1
-                    } else {
-                        (g.end_pts_of_contours[i] - g.end_pts_of_contours[i - 1]) as usize
-                    };
-
-                    let mut contour = Contour::new(contour_size + 1);
-
-                    for j in contour_start..=g.end_pts_of_contours[i] as usize {
-                        contour.points.push(Point {
-                            x: g.x_coordinates[j],
-                            y: g.y_coordinates[j],
-                            on_curve: expanded_flags[j] & 0x01 != 0,
-                        });
-                    }
-
-                    if !contour.points.is_empty() {
-                        let first_point = contour.points[0];
-                        contour.points.push(first_point);
-                    }
-
-                    transform_points(&mut contour.points, component);
-
-                    contour_start = g.end_pts_of_contours[i] as usize + 1;
-                    master.push(contour);
-                }
-            }
+        if walk.remaining == 0 {
+            return false;
+        }
+        walk.remaining -= 1;
 
+        let mut contours = match &font.get_glyph(glyph_id) {
+            ProtoGlyph::Simple(g) => simple_contours(g),
             ProtoGlyph::Composite(g) => {
-                load_from_parent(master, &g.components, font, font_bytes);
+                if walk.path.len() >= MAX_COMPONENT_DEPTH || walk.path.contains(&glyph_id) {
+                    return false;
+                }
+
+                let mut contours = Vec::new();
+                walk.path.push(glyph_id);
+                let loaded = load_from_parent(&mut contours, &g.components, font, walk);
+                walk.path.pop();
+
+                if !loaded {
+                    return false;
+                }
+                contours
             }
+            ProtoGlyph::Empty => continue,
+        };
 
-            ProtoGlyph::Empty => {}
+        let matrix = component_matrix(component);
+        let (dx, dy) = component_offset(component, &matrix, master, &contours);
+        let round = component.flags & (ARGS_ARE_XY_VALUES | ROUND_XY_TO_GRID) == ARGS_ARE_XY_VALUES | ROUND_XY_TO_GRID;
+
+        for contour in &mut contours {
+            transform_points(&mut contour.points, &matrix, dx, dy);
+
+            let (gx, gy) = apply_matrix(&matrix, contour.grid_offset.0, contour.grid_offset.1);
+            contour.grid_offset = if round { (gx + dx, gy + dy) } else { (gx, gy) };
         }
+
+        master.extend(contours);
     }
+
+    true
 }
 
 pub(crate) fn insert_midpoints(points: &mut Vec<Contour>) {
@@ -166,39 +212,77 @@ pub(crate) fn insert_midpoints(points: &mut Vec<Contour>) {
 }
 
 
-fn transform_points(points: &mut [Point], component: &CompositeComponent) {
-    let (x_scale, y_scale, scale_01, scale_10) = if component.flags & WE_HAVE_A_TWO_BY_TWO != 0 {
-        (
+/// The 2x2 transform of a component as `[xx, xy, yx, yy]`, where a point
+/// maps to `(x * xx + y * yx, x * xy + y * yy)`.
+fn component_matrix(component: &CompositeComponent) -> [f32; 4] {
+    if component.flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+        [
             component.x_scale.unwrap_or(1.0),
-            component.y_scale.unwrap_or(1.0),
             component.scale_01.unwrap_or(0.0),
             component.scale_10.unwrap_or(0.0),
-        )
+            component.y_scale.unwrap_or(1.0),
+        ]
     } else if component.flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
-        (component.x_scale.unwrap_or(1.0), component.y_scale.unwrap_or(1.0), 0.0, 0.0)
+        [component.x_scale.unwrap_or(1.0), 0.0, 0.0, component.y_scale.unwrap_or(1.0)]
     } else if component.flags & WE_HAVE_A_SCALE != 0 {
         let s = component.scale.unwrap_or(1.0);
-        (s, s, 0.0, 0.0)
+        [s, 0.0, 0.0, s]
     } else {
-        (1.0, 1.0, 0.0, 0.0)
-    };
-
-    for p in points.iter_mut() {
-        let old_x = p.x as f32;
-        let old_y = p.y as f32;
-        let nx = old_x * x_scale + old_y * scale_10;
-        let ny = old_x * scale_01 + old_y * y_scale;
-        p.x = nx.round() as i16;
-        p.y = ny.round() as i16;
+        [1.0, 0.0, 0.0, 1.0]
     }
+}
 
+fn apply_matrix(matrix: &[f32; 4], x: f32, y: f32) -> (f32, f32) {
+    (x * matrix[0] + y * matrix[2], x * matrix[1] + y * matrix[3])
+}
+
+/// The offset that places a component. With `ARGS_ARE_XY_VALUES` it is the
+/// argument pair, transformed along with the outline only when the font
+/// asks for `SCALED_COMPONENT_OFFSET`; otherwise it moves the component's
+/// point `argument2` onto point `argument1` of the glyph assembled so far.
+///
+/// Outlines are assembled once in font units for every size, so an offset
+/// flagged `ROUND_XY_TO_GRID` is recorded in `Contour::grid_offset` and
+/// rounded to the pixel grid when the glyph is rasterized.
+fn component_offset(component: &CompositeComponent, matrix: &[f32; 4], master: &[Contour], contours: &[Contour]) -> (f32, f32) {
     if component.flags & ARGS_ARE_XY_VALUES != 0 {
-        let dx = component.argument1;
-        let dy = component.argument2;
-        for p in points.iter_mut() {
-            p.x = p.x.wrapping_add(dx);
-            p.y = p.y.wrapping_add(dy);
+        let (dx, dy) = (component.argument1 as f32, component.argument2 as f32);
+        let scaled = component.flags & SCALED_COMPONENT_OFFSET != 0 && component.flags & UNSCALED_COMPONENT_OFFSET == 0;
+        if scaled { apply_matrix(matrix, dx, dy) } else { (dx, dy) }
+    } else {
+        let parent = point_number(master, component.argument1 as u16 as usize);
+        let child = point_number(contours, component.argument2 as u16 as usize);
+
+        match (parent, child) {
+            (Some(parent), Some(child)) => {
+                let (x, y) = apply_matrix(matrix, child.x as f32, child.y as f32);
+                (parent.x as f32 - x, parent.y as f32 - y)
+            }
+            // Phantom points and bad indices leave the component in place
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+/// Point `index` in TrueType numbering, which does not count the point
+/// repeated to close each contour.
+fn point_number(contours: &[Contour], mut index: usize) -> Option<Point> {
+    for contour in contours {
+        let len = contour.points.len().saturating_sub(1);
+        if index < len {
+            return Some(contour.points[index]);
         }
+        index -= len;
+    }
+
+    None
+}
+
+fn transform_points(points: &mut [Point], matrix: &[f32; 4], dx: f32, dy: f32) {
+    for p in points.iter_mut() {
+        let (x, y) = apply_matrix(matrix, p.x as f32, p.y as f32);
+        p.x = (x + dx).round() as i16;
+        p.y = (y + dy).round() as i16;
     }
 }
 
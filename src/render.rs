@@ -2,7 +2,9 @@ use crate::font::TrueTypeFont;
 use crate::F32NoStd;
 use crate::rasterizer::aet::rasterize;
 use crate::rasterizer::point::Contour;
+use crate::layout::LayoutOptions;
 use crate::Vec;
+use crate::vec;
 
 #[derive(Clone, Debug)]
 pub struct Metrics {
@@ -13,26 +15,165 @@ pub struct Metrics {
     pub base_line: isize,
 }
 
-impl TrueTypeFont {
-    pub fn get_char<const CACHE: bool>(&mut self, c: char, size: usize) -> (Metrics, Vec<u8>) {
+/// Font-wide vertical metrics in pixels at one size. Positions are heights
+/// above the baseline, so the descender and underline position are usually
+/// negative.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct LineMetrics {
+    pub ascender: f32,
+    pub descender: f32,
+    pub line_gap: f32,
+    /// Recommended distance between consecutive baselines.
+    pub line_height: f32,
+    pub x_height: f32,
+    pub cap_height: f32,
+    /// Top of the underline.
+    pub underline_position: f32,
+    pub underline_thickness: f32,
+    /// Top of the strikeout stroke.
+    pub strikeout_position: f32,
+    pub strikeout_thickness: f32,
+}
+
+/// An axis aligned box in pixels, with `y` growing downwards.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Bounds {
+    pub x_min: f32,
+    pub y_min: f32,
+    pub x_max: f32,
+    pub y_max: f32,
+}
+
+impl Bounds {
+    pub fn width(&self) -> f32 {
+        self.x_max - self.x_min
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y_max - self.y_min
+    }
+
+    /// The smallest box containing both `self` and `other`.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        Bounds {
+            x_min: self.x_min.min(other.x_min),
+            y_min: self.y_min.min(other.y_min),
+            x_max: self.x_max.max(other.x_max),
+            y_max: self.y_max.max(other.y_max),
+        }
+    }
 
+    fn offset(&self, dx: f32, dy: f32) -> Bounds {
+        Bounds { x_min: self.x_min + dx, y_min: self.y_min + dy, x_max: self.x_max + dx, y_max: self.y_max + dy }
+    }
+}
+
+/// A grayscale coverage image, one byte per pixel, row by row.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+/// Bounds of rendered text, in pixels relative to the top left of its bitmap.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextMetrics {
+    /// The layout box: line advances across and line heights down.
+    pub logical_bounds: Bounds,
+    /// The tight box around the glyph outlines. Empty at the logical origin
+    /// when the text has no visible glyphs.
+    pub ink_bounds: Bounds,
+    /// Baseline of the first line.
+    pub baseline: f32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    /// Wraps lines at this many pixels; `None` only breaks at newlines.
+    pub max_width: Option<f32>,
+    pub layout: LayoutOptions,
+}
+
+impl TrueTypeFont {
+    /// Pixels per font unit for a point size at 96 DPI.
+    pub fn scale_for_size(&self, size: usize) -> f32 {
         let dpi = 96.0;
         let pixels = size as f32 * dpi / 72.0;
-        let scale = pixels / self.head.units_per_em as f32;
+        pixels / self.head.units_per_em as f32
+    }
+
+    /// Line spacing and decoration metrics at `size`. Ascender, descender
+    /// and line gap come from the OS/2 typographic metrics when the font sets
+    /// `USE_TYPO_METRICS`, and from `hhea` otherwise. Heights the font does
+    /// not record are estimated from its outlines.
+    pub fn line_metrics(&self, size: usize) -> LineMetrics {
+        let scale = self.scale_for_size(size);
+        let units_per_em = self.head.units_per_em as i16;
+
+        let os2 = self.os2.as_ref();
+
+        let typo = os2
+            .filter(|os2| os2.use_typo_metrics())
+            .and_then(|os2| Some((os2.typo_ascender?, os2.typo_descender?, os2.typo_line_gap?)));
+        let (ascender, descender, line_gap) = typo.unwrap_or((self.hhea.ascender, self.hhea.descender, self.hhea.line_gap));
+
+        let outline_height = |c: char| self.glyph_bounds(self.get_glyph_id(c))
+            .map_or(0, |(_, _, _, y_max)| y_max);
+
+        let x_height = os2.and_then(|os2| os2.x_height).filter(|&h| h != 0).unwrap_or_else(|| outline_height('x'));
+        let cap_height = os2.and_then(|os2| os2.cap_height).filter(|&h| h != 0).unwrap_or_else(|| outline_height('H'));
+
+        let (underline_position, underline_thickness) = match &self.post {
+            Some(post) if post.underline_thickness != 0 => (post.underline_position, post.underline_thickness),
+            _ => (-units_per_em / 10, units_per_em / 20),
+        };
+        let (strikeout_position, strikeout_thickness) = match os2 {
+            Some(os2) if os2.strikeout_size != 0 => (os2.strikeout_position, os2.strikeout_size),
+            _ => (x_height / 2 + underline_thickness / 2, underline_thickness),
+        };
+
+        LineMetrics {
+            ascender: ascender as f32 * scale,
+            descender: descender as f32 * scale,
+            line_gap: line_gap as f32 * scale,
+            line_height: (ascender as f32 - descender as f32 + line_gap as f32) * scale,
+            x_height: x_height as f32 * scale,
+            cap_height: cap_height as f32 * scale,
+            underline_position: underline_position as f32 * scale,
+            underline_thickness: underline_thickness as f32 * scale,
+            strikeout_position: strikeout_position as f32 * scale,
+            strikeout_thickness: strikeout_thickness as f32 * scale,
+        }
+    }
+
+    pub fn get_char<const CACHE: bool>(&mut self, c: char, size: usize) -> (Metrics, Vec<u8>) {
+        self.get_glyph_bitmap::<CACHE>(self.get_glyph_id(c), size)
+    }
 
-        let id = self.glyph_id_table.get(&c).unwrap_or(&0);
+    /// The glyph drawn for `glyph_id`: the glyph itself, or `.notdef` when
+    /// the font has no glyph with that id.
+    fn drawn_glyph(&self, glyph_id: u32) -> u32 {
+        if glyph_id < self.maxp.num_glyphs as u32 { glyph_id } else { 0 }
+    }
+
+    /// Rasterizes a glyph by id, such as one returned by shaping or layout.
+    /// Ids past the end of the font draw `.notdef`.
+    pub fn get_glyph_bitmap<const CACHE: bool>(&mut self, glyph_id: u32, size: usize) -> (Metrics, Vec<u8>) {
+
+        let scale = self.scale_for_size(size);
+
+        let id = self.drawn_glyph(glyph_id);
 
         if CACHE {
-            let is_cached = self.cache.get(*id, size);
+            let is_cached = self.cache.get(id, size);
             if let Some(cached) = is_cached {
                 return cached.clone();
             }
         }
 
-        let glyph = self
-            .glyph_data_table
-            .get(&id)
-            .unwrap_or(self.glyph_data_table.get(&0).unwrap());
+        self.load_outline(id);
+        let glyph = &self.glyph_data_table[&id];
 
         let width = (((glyph.x_max - glyph.x_min) as f32 * scale).ceil() as usize) + 1;
         let height = (((glyph.y_max - glyph.y_min) as f32 * scale).ceil() as usize) + 1;
@@ -40,7 +181,7 @@ impl TrueTypeFont {
 
         let required_size = width * height;
 
-        let extra = self.get_metrics(id, scale);
+        let extra = self.get_metrics(&id, scale);
         let metrics = Metrics {
             width,
             height,
@@ -56,11 +197,81 @@ impl TrueTypeFont {
 
 
         if CACHE {
-            self.cache.set(*id, size, metrics.clone(), self.bitmap_buffer.clone());
+            self.cache.set(id, size, metrics.clone(), self.bitmap_buffer.clone());
         }
 
         (metrics, self.bitmap_buffer.clone())
     }
+
+    /// Lays out and rasterizes `text` into one bitmap. Glyphs are placed by
+    /// [`TrueTypeFont::layout`], so kerning and shaping apply, and coverage
+    /// of overlapping glyphs adds up, saturating at 255. The bitmap covers
+    /// both the logical and the ink bounds.
+    pub fn render_text(&mut self, text: &str, size: usize, options: &RenderOptions) -> (TextMetrics, Bitmap) {
+        let scale = self.scale_for_size(size);
+        let layout = self.layout(text, size, options.max_width, &options.layout);
+
+        let logical = Bounds { x_min: 0.0, y_min: 0.0, x_max: layout.width, y_max: layout.height };
+        let mut ink: Option<Bounds> = None;
+
+        // Glyphs without an outline, such as spaces, have no ink to draw
+        for glyph in layout.lines.iter().flat_map(|line| &line.glyphs) {
+            let Some((x_min, y_min, x_max, y_max)) = self.glyph_bounds(self.drawn_glyph(glyph.glyph_id)) else { continue };
+
+            let bounds = Bounds {
+                x_min: glyph.x + x_min as f32 * scale,
+                y_min: glyph.y - y_max as f32 * scale,
+                x_max: glyph.x + x_max as f32 * scale,
+                y_max: glyph.y - y_min as f32 * scale,
+            };
+            ink = Some(ink.map_or(bounds, |ink| ink.union(&bounds)));
+        }
+
+        let covered = ink.map_or(logical, |ink| ink.union(&logical));
+        let origin_x = covered.x_min.floor();
+        let origin_y = covered.y_min.floor();
+
+        let mut bitmap = Bitmap {
+            width: (covered.x_max - origin_x).ceil() as usize,
+            height: (covered.y_max - origin_y).ceil() as usize,
+            data: Vec::new(),
+        };
+        bitmap.data = vec![0; bitmap.width * bitmap.height];
+
+        for glyph in layout.lines.iter().flat_map(|line| &line.glyphs) {
+            let glyph_id = self.drawn_glyph(glyph.glyph_id);
+            let Some((x_min, _, _, y_max)) = self.glyph_bounds(glyph_id) else { continue };
+
+            let (metrics, coverage) = self.get_glyph_bitmap::<false>(glyph_id, size);
+            let left = (glyph.x + x_min as f32 * scale - origin_x).round() as isize;
+            let top = (glyph.y - y_max as f32 * scale - origin_y).round() as isize;
+
+            for row in 0..metrics.height {
+                let y = top + row as isize;
+                if y < 0 || y >= bitmap.height as isize {
+                    continue;
+                }
+
+                for column in 0..metrics.width {
+                    let x = left + column as isize;
+                    if x < 0 || x >= bitmap.width as isize {
+                        continue;
+                    }
+
+                    let pixel = &mut bitmap.data[y as usize * bitmap.width + x as usize];
+                    *pixel = pixel.saturating_add(coverage[row * metrics.width + column]);
+                }
+            }
+        }
+
+        let metrics = TextMetrics {
+            logical_bounds: logical.offset(-origin_x, -origin_y),
+            ink_bounds: ink.unwrap_or_default().offset(-origin_x, -origin_y),
+            baseline: layout.lines.first().map_or(0.0, |line| line.baseline) - origin_y,
+        };
+
+        (metrics, bitmap)
+    }
 }
 
 fn show_points(points: &[Contour], scale: f32, y_max: f32, x_min: f32, width: usize, height: usize, bitmap_buffer: &mut Vec<u8>) {
@@ -0,0 +1,163 @@
+mod tables;
+
+use core::ops::Range;
+
+use crate::bidi::{bracket, BidiInfo, BracketType};
+use crate::font::TrueTypeFont;
+use crate::shaping::{is_default_ignorable, Direction, Tag, DEFAULT_SCRIPT};
+use crate::vec;
+use crate::Vec;
+
+use tables::{SCRIPT_CODES, SCRIPT_RANGES};
+
+/// A Unicode script, identified by its ISO 15924 code such as `*b"Latn"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Script(pub [u8; 4]);
+
+impl Script {
+    pub const COMMON: Script = Script(*b"Zyyy");
+    pub const INHERITED: Script = Script(*b"Zinh");
+    pub const UNKNOWN: Script = Script(*b"Zzzz");
+
+    /// The `Script` property of a character.
+    pub fn of(c: char) -> Script {
+        let cp = c as u32;
+        match SCRIPT_RANGES.binary_search_by(|&(start, end, _)| {
+            if cp < start {
+                core::cmp::Ordering::Greater
+            } else if cp > end {
+                core::cmp::Ordering::Less
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        }) {
+            Ok(i) => Script(SCRIPT_CODES[SCRIPT_RANGES[i].2 as usize]),
+            Err(_) => Script::UNKNOWN,
+        }
+    }
+
+    /// Whether this is a real script rather than Common, Inherited or Unknown.
+    pub fn is_explicit(self) -> bool {
+        self != Script::COMMON && self != Script::INHERITED && self != Script::UNKNOWN
+    }
+
+    /// The OpenType script tag used to select GSUB and GPOS lookups.
+    pub fn opentype_tag(self) -> Tag {
+        match &self.0 {
+            b"Zyyy" | b"Zinh" | b"Zzzz" => DEFAULT_SCRIPT,
+            b"Hira" => *b"kana",
+            b"Laoo" => *b"lao ",
+            b"Nkoo" => *b"nko ",
+            b"Vaii" => *b"vai ",
+            b"Yiii" => *b"yi  ",
+            code => code.map(|b| b.to_ascii_lowercase()),
+        }
+    }
+}
+
+/// A run of text with one script, one bidi embedding level and one font.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextRun {
+    /// Byte range in the source text.
+    pub range: Range<usize>,
+    pub script: Script,
+    /// Bidi embedding level; odd levels are right-to-left.
+    pub level: u8,
+    /// Index of the font covering the run, always 0 for [`itemize`].
+    pub font: usize,
+}
+
+impl TextRun {
+    pub fn direction(&self) -> Direction {
+        if self.level % 2 == 1 { Direction::RightToLeft } else { Direction::LeftToRight }
+    }
+}
+
+/// The resolved script of every byte of `text`. Common and Inherited
+/// characters take the script of the text before them (or after them at
+/// the start of the text), and a closing bracket takes the script of its
+/// opening bracket.
+pub(crate) fn resolve_scripts(text: &str) -> Vec<Script> {
+    let mut scripts = vec![Script::COMMON; text.len()];
+    let mut last = Script::COMMON;
+    let mut first_explicit = None;
+    let mut brackets: Vec<(u32, Script)> = Vec::new();
+
+    for (offset, c) in text.char_indices() {
+        let mut script = Script::of(c);
+
+        if script == Script::INHERITED {
+            script = last;
+        } else if !script.is_explicit() {
+            script = match bracket(c) {
+                Some((closing, BracketType::Open)) => {
+                    brackets.push((closing, last));
+                    last
+                }
+                Some((_, BracketType::Close)) => match brackets.iter().rposition(|&(closing, _)| closing == c as u32) {
+                    Some(depth) => {
+                        let opening = brackets[depth].1;
+                        brackets.truncate(depth);
+                        if opening.is_explicit() { opening } else { last }
+                    }
+                    None => last,
+                },
+                None => last,
+            };
+        }
+
+        if script.is_explicit() {
+            first_explicit.get_or_insert(script);
+        }
+
+        last = script;
+        scripts[offset..offset + c.len_utf8()].fill(script);
+    }
+
+    if let Some(first) = first_explicit {
+        for script in scripts.iter_mut().take_while(|s| !s.is_explicit()) {
+            *script = first;
+        }
+    }
+
+    scripts
+}
+
+/// Splits `text` into runs of uniform script and bidi level, in logical order.
+pub fn itemize(text: &str) -> Vec<TextRun> {
+    itemize_with_fonts(text, &[])
+}
+
+/// Like [`itemize`], but also splits runs by font: each character goes to
+/// the first font in `fonts` that has a glyph for it. Marks and other
+/// combining characters stay with the font of their base, and punctuation
+/// and spaces stay with the previous font when it covers them.
+pub fn itemize_with_fonts(text: &str, fonts: &[&TrueTypeFont]) -> Vec<TextRun> {
+    let scripts = resolve_scripts(text);
+    let levels = BidiInfo::new(text, None).levels;
+
+    let mut runs: Vec<TextRun> = Vec::new();
+    let mut font = 0;
+
+    for (offset, c) in text.char_indices() {
+        let end = offset + c.len_utf8();
+        let has = |f: usize| fonts.get(f).is_some_and(|font| font.get_glyph_id(c) != 0);
+
+        let script = Script::of(c);
+        let sticky = offset > 0 && (script == Script::INHERITED || is_default_ignorable(c)
+            || (!script.is_explicit() && has(font)));
+
+        if !sticky {
+            font = (0..fonts.len()).find(|&f| has(f)).unwrap_or(if offset > 0 { font } else { 0 });
+        }
+
+        match runs.last_mut() {
+            Some(run) if run.script == scripts[offset] && run.level == levels[offset] && run.font == font => {
+                run.range.end = end;
+            }
+            _ => runs.push(TextRun { range: offset..end, script: scripts[offset], level: levels[offset], font }),
+        }
+    }
+
+    runs
+}
@@ -0,0 +1,471 @@
+// Generated from the Unicode 17.0 Script property. Ranges of one script
+// are merged across unassigned code points; code points outside every
+// range are Unknown (Zzzz).
+
+pub(crate) const SCRIPT_CODES: [[u8; 4]; 174] = [
+    *b"Zyyy", *b"Zinh", *b"Adlm", *b"Aghb", *b"Ahom", *b"Arab", *b"Armi", *b"Armn",
+    *b"Avst", *b"Bali", *b"Bamu", *b"Bass", *b"Batk", *b"Beng", *b"Berf", *b"Bhks",
+    *b"Bopo", *b"Brah", *b"Brai", *b"Bugi", *b"Buhd", *b"Cakm", *b"Cans", *b"Cari",
+    *b"Cham", *b"Cher", *b"Chrs", *b"Copt", *b"Cpmn", *b"Cprt", *b"Cyrl", *b"Deva",
+    *b"Diak", *b"Dogr", *b"Dsrt", *b"Dupl", *b"Egyp", *b"Elba", *b"Elym", *b"Ethi",
+    *b"Gara", *b"Geor", *b"Glag", *b"Gong", *b"Gonm", *b"Goth", *b"Gran", *b"Grek",
+    *b"Gujr", *b"Gukh", *b"Guru", *b"Hang", *b"Hani", *b"Hano", *b"Hatr", *b"Hebr",
+    *b"Hira", *b"Hluw", *b"Hmng", *b"Hmnp", *b"Hung", *b"Ital", *b"Java", *b"Kali",
+    *b"Kana", *b"Kawi", *b"Khar", *b"Khmr", *b"Khoj", *b"Kits", *b"Knda", *b"Krai",
+    *b"Kthi", *b"Lana", *b"Laoo", *b"Latn", *b"Lepc", *b"Limb", *b"Lina", *b"Linb",
+    *b"Lisu", *b"Lyci", *b"Lydi", *b"Mahj", *b"Maka", *b"Mand", *b"Mani", *b"Marc",
+    *b"Medf", *b"Mend", *b"Merc", *b"Mero", *b"Mlym", *b"Modi", *b"Mong", *b"Mroo",
+    *b"Mtei", *b"Mult", *b"Mymr", *b"Nagm", *b"Nand", *b"Narb", *b"Nbat", *b"Newa",
+    *b"Nkoo", *b"Nshu", *b"Ogam", *b"Olck", *b"Onao", *b"Orkh", *b"Orya", *b"Osge",
+    *b"Osma", *b"Ougr", *b"Palm", *b"Pauc", *b"Perm", *b"Phag", *b"Phli", *b"Phlp",
+    *b"Phnx", *b"Plrd", *b"Prti", *b"Rjng", *b"Rohg", *b"Runr", *b"Samr", *b"Sarb",
+    *b"Saur", *b"Sgnw", *b"Shaw", *b"Shrd", *b"Sidd", *b"Sidt", *b"Sind", *b"Sinh",
+    *b"Sogd", *b"Sogo", *b"Sora", *b"Soyo", *b"Sund", *b"Sunu", *b"Sylo", *b"Syrc",
+    *b"Tagb", *b"Takr", *b"Tale", *b"Talu", *b"Taml", *b"Tang", *b"Tavt", *b"Tayo",
+    *b"Telu", *b"Tfng", *b"Tglg", *b"Thaa", *b"Thai", *b"Tibt", *b"Tirh", *b"Tnsa",
+    *b"Todr", *b"Tols", *b"Toto", *b"Tutg", *b"Ugar", *b"Vaii", *b"Vith", *b"Wara",
+    *b"Wcho", *b"Xpeo", *b"Xsux", *b"Yezi", *b"Yiii", *b"Zanb",
+];
+
+/// `(first, last, index into SCRIPT_CODES)`
+pub(crate) const SCRIPT_RANGES: &[(u32, u32, u8)] = &[
+    (0x0000, 0x0040, 0),
+    (0x0041, 0x005A, 75),
+    (0x005B, 0x0060, 0),
+    (0x0061, 0x007A, 75),
+    (0x007B, 0x00A9, 0),
+    (0x00AA, 0x00AA, 75),
+    (0x00AB, 0x00B9, 0),
+    (0x00BA, 0x00BA, 75),
+    (0x00BB, 0x00BF, 0),
+    (0x00C0, 0x00D6, 75),
+    (0x00D7, 0x00D7, 0),
+    (0x00D8, 0x00F6, 75),
+    (0x00F7, 0x00F7, 0),
+    (0x00F8, 0x02B8, 75),
+    (0x02B9, 0x02DF, 0),
+    (0x02E0, 0x02E4, 75),
+    (0x02E5, 0x02E9, 0),
+    (0x02EA, 0x02EB, 16),
+    (0x02EC, 0x02FF, 0),
+    (0x0300, 0x036F, 1),
+    (0x0370, 0x0373, 47),
+    (0x0374, 0x0374, 0),
+    (0x0375, 0x037D, 47),
+    (0x037E, 0x037E, 0),
+    (0x037F, 0x0384, 47),
+    (0x0385, 0x0385, 0),
+    (0x0386, 0x0386, 47),
+    (0x0387, 0x0387, 0),
+    (0x0388, 0x03E1, 47),
+    (0x03E2, 0x03EF, 27),
+    (0x03F0, 0x03FF, 47),
+    (0x0400, 0x0484, 30),
+    (0x0485, 0x0486, 1),
+    (0x0487, 0x052F, 30),
+    (0x0531, 0x058F, 7),
+    (0x0591, 0x05F4, 55),
+    (0x0600, 0x0604, 5),
+    (0x0605, 0x0605, 0),
+    (0x0606, 0x060B, 5),
+    (0x060C, 0x060C, 0),
+    (0x060D, 0x061A, 5),
+    (0x061B, 0x061B, 0),
+    (0x061C, 0x061E, 5),
+    (0x061F, 0x061F, 0),
+    (0x0620, 0x063F, 5),
+    (0x0640, 0x0640, 0),
+    (0x0641, 0x064A, 5),
+    (0x064B, 0x0655, 1),
+    (0x0656, 0x066F, 5),
+    (0x0670, 0x0670, 1),
+    (0x0671, 0x06DC, 5),
+    (0x06DD, 0x06DD, 0),
+    (0x06DE, 0x06FF, 5),
+    (0x0700, 0x074F, 143),
+    (0x0750, 0x077F, 5),
+    (0x0780, 0x07B1, 155),
+    (0x07C0, 0x07FF, 104),
+    (0x0800, 0x083E, 126),
+    (0x0840, 0x085E, 85),
+    (0x0860, 0x086A, 143),
+    (0x0870, 0x08E1, 5),
+    (0x08E2, 0x08E2, 0),
+    (0x08E3, 0x08FF, 5),
+    (0x0900, 0x0950, 31),
+    (0x0951, 0x0954, 1),
+    (0x0955, 0x0963, 31),
+    (0x0964, 0x0965, 0),
+    (0x0966, 0x097F, 31),
+    (0x0980, 0x09FE, 13),
+    (0x0A01, 0x0A76, 50),
+    (0x0A81, 0x0AFF, 48),
+    (0x0B01, 0x0B77, 110),
+    (0x0B82, 0x0BFA, 148),
+    (0x0C00, 0x0C7F, 152),
+    (0x0C80, 0x0CF3, 70),
+    (0x0D00, 0x0D7F, 92),
+    (0x0D81, 0x0DF4, 135),
+    (0x0E01, 0x0E3A, 156),
+    (0x0E3F, 0x0E3F, 0),
+    (0x0E40, 0x0E5B, 156),
+    (0x0E81, 0x0EDF, 74),
+    (0x0F00, 0x0FD4, 157),
+    (0x0FD5, 0x0FD8, 0),
+    (0x0FD9, 0x0FDA, 157),
+    (0x1000, 0x109F, 98),
+    (0x10A0, 0x10FA, 41),
+    (0x10FB, 0x10FB, 0),
+    (0x10FC, 0x10FF, 41),
+    (0x1100, 0x11FF, 51),
+    (0x1200, 0x1399, 39),
+    (0x13A0, 0x13FD, 25),
+    (0x1400, 0x167F, 22),
+    (0x1680, 0x169C, 106),
+    (0x16A0, 0x16EA, 125),
+    (0x16EB, 0x16ED, 0),
+    (0x16EE, 0x16F8, 125),
+    (0x1700, 0x171F, 154),
+    (0x1720, 0x1734, 53),
+    (0x1735, 0x1736, 0),
+    (0x1740, 0x1753, 20),
+    (0x1760, 0x1773, 144),
+    (0x1780, 0x17F9, 67),
+    (0x1800, 0x1801, 94),
+    (0x1802, 0x1803, 0),
+    (0x1804, 0x1804, 94),
+    (0x1805, 0x1805, 0),
+    (0x1806, 0x18AA, 94),
+    (0x18B0, 0x18F5, 22),
+    (0x1900, 0x194F, 77),
+    (0x1950, 0x1974, 146),
+    (0x1980, 0x19DF, 147),
+    (0x19E0, 0x19FF, 67),
+    (0x1A00, 0x1A1F, 19),
+    (0x1A20, 0x1AAD, 73),
+    (0x1AB0, 0x1AEB, 1),
+    (0x1B00, 0x1B7F, 9),
+    (0x1B80, 0x1BBF, 140),
+    (0x1BC0, 0x1BFF, 12),
+    (0x1C00, 0x1C4F, 76),
+    (0x1C50, 0x1C7F, 107),
+    (0x1C80, 0x1C8A, 30),
+    (0x1C90, 0x1CBF, 41),
+    (0x1CC0, 0x1CC7, 140),
+    (0x1CD0, 0x1CD2, 1),
+    (0x1CD3, 0x1CD3, 0),
+    (0x1CD4, 0x1CE0, 1),
+    (0x1CE1, 0x1CE1, 0),
+    (0x1CE2, 0x1CE8, 1),
+    (0x1CE9, 0x1CEC, 0),
+    (0x1CED, 0x1CED, 1),
+    (0x1CEE, 0x1CF3, 0),
+    (0x1CF4, 0x1CF4, 1),
+    (0x1CF5, 0x1CF7, 0),
+    (0x1CF8, 0x1CF9, 1),
+    (0x1CFA, 0x1CFA, 0),
+    (0x1D00, 0x1D25, 75),
+    (0x1D26, 0x1D2A, 47),
+    (0x1D2B, 0x1D2B, 30),
+    (0x1D2C, 0x1D5C, 75),
+    (0x1D5D, 0x1D61, 47),
+    (0x1D62, 0x1D65, 75),
+    (0x1D66, 0x1D6A, 47),
+    (0x1D6B, 0x1D77, 75),
+    (0x1D78, 0x1D78, 30),
+    (0x1D79, 0x1DBE, 75),
+    (0x1DBF, 0x1DBF, 47),
+    (0x1DC0, 0x1DFF, 1),
+    (0x1E00, 0x1EFF, 75),
+    (0x1F00, 0x1FFE, 47),
+    (0x2000, 0x200B, 0),
+    (0x200C, 0x200D, 1),
+    (0x200E, 0x2070, 0),
+    (0x2071, 0x2071, 75),
+    (0x2074, 0x207E, 0),
+    (0x207F, 0x207F, 75),
+    (0x2080, 0x208E, 0),
+    (0x2090, 0x209C, 75),
+    (0x20A0, 0x20C1, 0),
+    (0x20D0, 0x20F0, 1),
+    (0x2100, 0x2125, 0),
+    (0x2126, 0x2126, 47),
+    (0x2127, 0x2129, 0),
+    (0x212A, 0x212B, 75),
+    (0x212C, 0x2131, 0),
+    (0x2132, 0x2132, 75),
+    (0x2133, 0x214D, 0),
+    (0x214E, 0x214E, 75),
+    (0x214F, 0x215F, 0),
+    (0x2160, 0x2188, 75),
+    (0x2189, 0x27FF, 0),
+    (0x2800, 0x28FF, 18),
+    (0x2900, 0x2BFF, 0),
+    (0x2C00, 0x2C5F, 42),
+    (0x2C60, 0x2C7F, 75),
+    (0x2C80, 0x2CFF, 27),
+    (0x2D00, 0x2D2D, 41),
+    (0x2D30, 0x2D7F, 153),
+    (0x2D80, 0x2DDE, 39),
+    (0x2DE0, 0x2DFF, 30),
+    (0x2E00, 0x2E5D, 0),
+    (0x2E80, 0x2FD5, 52),
+    (0x2FF0, 0x3004, 0),
+    (0x3005, 0x3005, 52),
+    (0x3006, 0x3006, 0),
+    (0x3007, 0x3007, 52),
+    (0x3008, 0x3020, 0),
+    (0x3021, 0x3029, 52),
+    (0x302A, 0x302D, 1),
+    (0x302E, 0x302F, 51),
+    (0x3030, 0x3037, 0),
+    (0x3038, 0x303B, 52),
+    (0x303C, 0x303F, 0),
+    (0x3041, 0x3096, 56),
+    (0x3099, 0x309A, 1),
+    (0x309B, 0x309C, 0),
+    (0x309D, 0x309F, 56),
+    (0x30A0, 0x30A0, 0),
+    (0x30A1, 0x30FA, 64),
+    (0x30FB, 0x30FC, 0),
+    (0x30FD, 0x30FF, 64),
+    (0x3105, 0x312F, 16),
+    (0x3131, 0x318E, 51),
+    (0x3190, 0x319F, 0),
+    (0x31A0, 0x31BF, 16),
+    (0x31C0, 0x31EF, 0),
+    (0x31F0, 0x31FF, 64),
+    (0x3200, 0x321E, 51),
+    (0x3220, 0x325F, 0),
+    (0x3260, 0x327E, 51),
+    (0x327F, 0x32CF, 0),
+    (0x32D0, 0x32FE, 64),
+    (0x32FF, 0x32FF, 0),
+    (0x3300, 0x3357, 64),
+    (0x3358, 0x33FF, 0),
+    (0x3400, 0x4DBF, 52),
+    (0x4DC0, 0x4DFF, 0),
+    (0x4E00, 0x9FFF, 52),
+    (0xA000, 0xA4C6, 172),
+    (0xA4D0, 0xA4FF, 80),
+    (0xA500, 0xA62B, 165),
+    (0xA640, 0xA69F, 30),
+    (0xA6A0, 0xA6F7, 10),
+    (0xA700, 0xA721, 0),
+    (0xA722, 0xA787, 75),
+    (0xA788, 0xA78A, 0),
+    (0xA78B, 0xA7FF, 75),
+    (0xA800, 0xA82C, 142),
+    (0xA830, 0xA839, 0),
+    (0xA840, 0xA877, 117),
+    (0xA880, 0xA8D9, 128),
+    (0xA8E0, 0xA8FF, 31),
+    (0xA900, 0xA92D, 63),
+    (0xA92E, 0xA92E, 0),
+    (0xA92F, 0xA92F, 63),
+    (0xA930, 0xA95F, 123),
+    (0xA960, 0xA97C, 51),
+    (0xA980, 0xA9CD, 62),
+    (0xA9CF, 0xA9CF, 0),
+    (0xA9D0, 0xA9DF, 62),
+    (0xA9E0, 0xA9FE, 98),
+    (0xAA00, 0xAA5F, 24),
+    (0xAA60, 0xAA7F, 98),
+    (0xAA80, 0xAADF, 150),
+    (0xAAE0, 0xAAF6, 96),
+    (0xAB01, 0xAB2E, 39),
+    (0xAB30, 0xAB5A, 75),
+    (0xAB5B, 0xAB5B, 0),
+    (0xAB5C, 0xAB64, 75),
+    (0xAB65, 0xAB65, 47),
+    (0xAB66, 0xAB69, 75),
+    (0xAB6A, 0xAB6B, 0),
+    (0xAB70, 0xABBF, 25),
+    (0xABC0, 0xABF9, 96),
+    (0xAC00, 0xD7FB, 51),
+    (0xF900, 0xFAD9, 52),
+    (0xFB00, 0xFB06, 75),
+    (0xFB13, 0xFB17, 7),
+    (0xFB1D, 0xFB4F, 55),
+    (0xFB50, 0xFD3D, 5),
+    (0xFD3E, 0xFD3F, 0),
+    (0xFD40, 0xFDFF, 5),
+    (0xFE00, 0xFE0F, 1),
+    (0xFE10, 0xFE19, 0),
+    (0xFE20, 0xFE2D, 1),
+    (0xFE2E, 0xFE2F, 30),
+    (0xFE30, 0xFE6B, 0),
+    (0xFE70, 0xFEFC, 5),
+    (0xFEFF, 0xFF20, 0),
+    (0xFF21, 0xFF3A, 75),
+    (0xFF3B, 0xFF40, 0),
+    (0xFF41, 0xFF5A, 75),
+    (0xFF5B, 0xFF65, 0),
+    (0xFF66, 0xFF6F, 64),
+    (0xFF70, 0xFF70, 0),
+    (0xFF71, 0xFF9D, 64),
+    (0xFF9E, 0xFF9F, 0),
+    (0xFFA0, 0xFFDC, 51),
+    (0xFFE0, 0xFFFD, 0),
+    (0x10000, 0x100FA, 79),
+    (0x10100, 0x1013F, 0),
+    (0x10140, 0x1018E, 47),
+    (0x10190, 0x1019C, 0),
+    (0x101A0, 0x101A0, 47),
+    (0x101D0, 0x101FC, 0),
+    (0x101FD, 0x101FD, 1),
+    (0x10280, 0x1029C, 81),
+    (0x102A0, 0x102D0, 23),
+    (0x102E0, 0x102E0, 1),
+    (0x102E1, 0x102FB, 0),
+    (0x10300, 0x1032F, 61),
+    (0x10330, 0x1034A, 45),
+    (0x10350, 0x1037A, 116),
+    (0x10380, 0x1039F, 164),
+    (0x103A0, 0x103D5, 169),
+    (0x10400, 0x1044F, 34),
+    (0x10450, 0x1047F, 130),
+    (0x10480, 0x104A9, 112),
+    (0x104B0, 0x104FB, 111),
+    (0x10500, 0x10527, 37),
+    (0x10530, 0x1056F, 3),
+    (0x10570, 0x105BC, 166),
+    (0x105C0, 0x105F3, 160),
+    (0x10600, 0x10767, 78),
+    (0x10780, 0x107BA, 75),
+    (0x10800, 0x1083F, 29),
+    (0x10840, 0x1085F, 6),
+    (0x10860, 0x1087F, 114),
+    (0x10880, 0x108AF, 102),
+    (0x108E0, 0x108FF, 54),
+    (0x10900, 0x1091F, 120),
+    (0x10920, 0x1093F, 82),
+    (0x10940, 0x10959, 133),
+    (0x10980, 0x1099F, 91),
+    (0x109A0, 0x109FF, 90),
+    (0x10A00, 0x10A58, 66),
+    (0x10A60, 0x10A7F, 127),
+    (0x10A80, 0x10A9F, 101),
+    (0x10AC0, 0x10AF6, 86),
+    (0x10B00, 0x10B3F, 8),
+    (0x10B40, 0x10B5F, 122),
+    (0x10B60, 0x10B7F, 118),
+    (0x10B80, 0x10BAF, 119),
+    (0x10C00, 0x10C48, 109),
+    (0x10C80, 0x10CFF, 60),
+    (0x10D00, 0x10D39, 124),
+    (0x10D40, 0x10D8F, 40),
+    (0x10E60, 0x10E7E, 5),
+    (0x10E80, 0x10EB1, 171),
+    (0x10EC2, 0x10EFF, 5),
+    (0x10F00, 0x10F27, 137),
+    (0x10F30, 0x10F59, 136),
+    (0x10F70, 0x10F89, 113),
+    (0x10FB0, 0x10FCB, 26),
+    (0x10FE0, 0x10FF6, 38),
+    (0x11000, 0x1107F, 17),
+    (0x11080, 0x110CD, 72),
+    (0x110D0, 0x110F9, 138),
+    (0x11100, 0x11147, 21),
+    (0x11150, 0x11176, 83),
+    (0x11180, 0x111DF, 131),
+    (0x111E1, 0x111F4, 135),
+    (0x11200, 0x11241, 68),
+    (0x11280, 0x112A9, 97),
+    (0x112B0, 0x112F9, 134),
+    (0x11300, 0x11339, 46),
+    (0x1133B, 0x1133B, 1),
+    (0x1133C, 0x11374, 46),
+    (0x11380, 0x113E2, 163),
+    (0x11400, 0x11461, 103),
+    (0x11480, 0x114D9, 158),
+    (0x11580, 0x115DD, 132),
+    (0x11600, 0x11659, 93),
+    (0x11660, 0x1166C, 94),
+    (0x11680, 0x116C9, 145),
+    (0x116D0, 0x116E3, 98),
+    (0x11700, 0x11746, 4),
+    (0x11800, 0x1183B, 33),
+    (0x118A0, 0x118FF, 167),
+    (0x11900, 0x11959, 32),
+    (0x119A0, 0x119E4, 100),
+    (0x11A00, 0x11A47, 173),
+    (0x11A50, 0x11AA2, 139),
+    (0x11AB0, 0x11ABF, 22),
+    (0x11AC0, 0x11AF8, 115),
+    (0x11B00, 0x11B09, 31),
+    (0x11B60, 0x11B67, 131),
+    (0x11BC0, 0x11BF9, 141),
+    (0x11C00, 0x11C6C, 15),
+    (0x11C70, 0x11CB6, 87),
+    (0x11D00, 0x11D59, 44),
+    (0x11D60, 0x11DA9, 43),
+    (0x11DB0, 0x11DE9, 161),
+    (0x11EE0, 0x11EF8, 84),
+    (0x11F00, 0x11F5A, 65),
+    (0x11FB0, 0x11FB0, 80),
+    (0x11FC0, 0x11FFF, 148),
+    (0x12000, 0x12543, 170),
+    (0x12F90, 0x12FF2, 28),
+    (0x13000, 0x143FA, 36),
+    (0x14400, 0x14646, 57),
+    (0x16100, 0x16139, 49),
+    (0x16800, 0x16A38, 10),
+    (0x16A40, 0x16A6F, 95),
+    (0x16A70, 0x16AC9, 159),
+    (0x16AD0, 0x16AF5, 11),
+    (0x16B00, 0x16B8F, 58),
+    (0x16D40, 0x16D79, 71),
+    (0x16E40, 0x16E9A, 88),
+    (0x16EA0, 0x16ED3, 14),
+    (0x16F00, 0x16F9F, 121),
+    (0x16FE0, 0x16FE0, 149),
+    (0x16FE1, 0x16FE1, 105),
+    (0x16FE2, 0x16FE3, 52),
+    (0x16FE4, 0x16FE4, 69),
+    (0x16FF0, 0x16FF6, 52),
+    (0x17000, 0x18AFF, 149),
+    (0x18B00, 0x18CFF, 69),
+    (0x18D00, 0x18DF2, 149),
+    (0x1AFF0, 0x1B000, 64),
+    (0x1B001, 0x1B11F, 56),
+    (0x1B120, 0x1B122, 64),
+    (0x1B132, 0x1B152, 56),
+    (0x1B155, 0x1B167, 64),
+    (0x1B170, 0x1B2FB, 105),
+    (0x1BC00, 0x1BC9F, 35),
+    (0x1BCA0, 0x1CEF0, 0),
+    (0x1CF00, 0x1CF46, 1),
+    (0x1CF50, 0x1D166, 0),
+    (0x1D167, 0x1D169, 1),
+    (0x1D16A, 0x1D17A, 0),
+    (0x1D17B, 0x1D182, 1),
+    (0x1D183, 0x1D184, 0),
+    (0x1D185, 0x1D18B, 1),
+    (0x1D18C, 0x1D1A9, 0),
+    (0x1D1AA, 0x1D1AD, 1),
+    (0x1D1AE, 0x1D1EA, 0),
+    (0x1D200, 0x1D245, 47),
+    (0x1D2C0, 0x1D7FF, 0),
+    (0x1D800, 0x1DAAF, 129),
+    (0x1DF00, 0x1DF2A, 75),
+    (0x1E000, 0x1E02A, 42),
+    (0x1E030, 0x1E08F, 30),
+    (0x1E100, 0x1E14F, 59),
+    (0x1E290, 0x1E2AE, 162),
+    (0x1E2C0, 0x1E2FF, 168),
+    (0x1E4D0, 0x1E4F9, 99),
+    (0x1E5D0, 0x1E5FF, 108),
+    (0x1E6C0, 0x1E6FF, 151),
+    (0x1E7E0, 0x1E7FE, 39),
+    (0x1E800, 0x1E8D6, 89),
+    (0x1E900, 0x1E95F, 2),
+    (0x1EC71, 0x1ED3D, 0),
+    (0x1EE00, 0x1EEF1, 5),
+    (0x1F000, 0x1F1FF, 0),
+    (0x1F200, 0x1F200, 56),
+    (0x1F201, 0x1FBFA, 0),
+    (0x20000, 0x33479, 52),
+    (0xE0001, 0xE007F, 0),
+    (0xE0100, 0xE01EF, 1),
+];
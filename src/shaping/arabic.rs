@@ -0,0 +1,445 @@
+use crate::font::TrueTypeFont;
+use crate::shaping::{shaped_glyphs, Direction, Feature, GlyphBuffer, ShapedGlyph, ShapingOptions};
+use crate::Vec;
+
+const ISOL_MASK: u32 = 1 << 1;
+const FINA_MASK: u32 = 1 << 2;
+const MEDI_MASK: u32 = 1 << 3;
+const INIT_MASK: u32 = 1 << 4;
+
+const LAM: char = '\u{0644}';
+
+/// Unicode `Joining_Type` property.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum JoiningType {
+    NonJoining,
+    Dual,
+    Right,
+    Causing,
+    Transparent,
+}
+
+impl JoiningType {
+    fn joins_to_next(self) -> bool {
+        matches!(self, JoiningType::Dual | JoiningType::Causing)
+    }
+
+    fn joins_to_prev(self) -> bool {
+        matches!(self, JoiningType::Dual | JoiningType::Right | JoiningType::Causing)
+    }
+
+    fn has_forms(self) -> bool {
+        matches!(self, JoiningType::Dual | JoiningType::Right)
+    }
+}
+
+/// Contextual form of a joining character.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Form {
+    None,
+    Isolated,
+    Final,
+    Initial,
+    Medial,
+}
+
+impl Form {
+    fn mask(self) -> u32 {
+        match self {
+            Form::None => 0,
+            Form::Isolated => ISOL_MASK,
+            Form::Final => FINA_MASK,
+            Form::Initial => INIT_MASK,
+            Form::Medial => MEDI_MASK,
+        }
+    }
+}
+
+pub(crate) fn joining_type(c: char) -> JoiningType {
+    let cp = c as u32;
+    match JOINING_TYPES.binary_search_by(|&(start, end, _)| {
+        if cp < start {
+            core::cmp::Ordering::Greater
+        } else if cp > end {
+            core::cmp::Ordering::Less
+        } else {
+            core::cmp::Ordering::Equal
+        }
+    }) {
+        Ok(i) => JOINING_TYPES[i].2,
+        Err(_) => JoiningType::NonJoining,
+    }
+}
+
+/// Resolves the contextual form of every character. Transparent characters
+/// (marks) are skipped over, ZWJ and tatweel cause joining, and ZWNJ or any
+/// other non-joining character breaks it.
+pub(crate) fn joining_forms(chars: &[char]) -> Vec<Form> {
+    let mut forms: Vec<Form> = chars.iter()
+        .map(|&c| if joining_type(c).has_forms() { Form::Isolated } else { Form::None })
+        .collect();
+
+    let mut prev: Option<(usize, JoiningType)> = None;
+
+    for (i, &c) in chars.iter().enumerate() {
+        let current = joining_type(c);
+        if current == JoiningType::Transparent {
+            continue;
+        }
+
+        if let Some((p, prev_type)) = prev
+            && prev_type.joins_to_next()
+            && current.joins_to_prev()
+        {
+            forms[p] = match forms[p] {
+                Form::Isolated => Form::Initial,
+                Form::Final => Form::Medial,
+                other => other,
+            };
+
+            if current.has_forms() {
+                forms[i] = Form::Final;
+            }
+        }
+
+        prev = Some((i, current));
+    }
+
+    forms
+}
+
+impl TrueTypeFont {
+    /// Shapes Arabic script text: joining forms are selected through the
+    /// `isol`/`init`/`medi`/`fina` features, or through the presentation
+    /// forms in the cmap when the font has no GSUB support for them.
+    pub(crate) fn shape_arabic(&self, text: &str, options: &ShapingOptions) -> Vec<ShapedGlyph> {
+        let chars: Vec<char> = text.chars().collect();
+        let forms = joining_forms(&chars);
+
+        let mut buffer = self.glyph_buffer(text);
+        buffer.direction = Direction::RightToLeft;
+        self.mirror_glyphs(&mut buffer, text);
+
+        for (info, form) in buffer.glyphs.iter_mut().zip(forms.iter()) {
+            info.mask |= form.mask();
+        }
+
+        let has_gsub_forms = [*b"init", *b"medi", *b"fina"].iter()
+            .any(|&tag| self.gsub.has_feature(options.script, options.language, tag));
+
+        if !has_gsub_forms {
+            self.apply_presentation_forms(&mut buffer, &chars, &forms);
+        }
+
+        let extra = [
+            (Feature::on(*b"isol"), ISOL_MASK),
+            (Feature::on(*b"fina"), FINA_MASK),
+            (Feature::on(*b"medi"), MEDI_MASK),
+            (Feature::on(*b"init"), INIT_MASK),
+        ];
+
+        self.substitute_default(&mut buffer, options, &extra);
+        self.position_default(&mut buffer, options, &[]);
+        self.hide_default_ignorables(&mut buffer, text);
+
+        shaped_glyphs(buffer)
+    }
+
+    /// Fallback shaping for fonts that only map the Arabic Presentation
+    /// Forms-B block: swaps letters for their contextual forms and forms
+    /// mandatory lam-alef ligatures.
+    fn apply_presentation_forms(&self, buffer: &mut GlyphBuffer, chars: &[char], forms: &[Form]) {
+        let form_glyph = |c: char, form: Form| -> Option<u32> {
+            let index = match form {
+                Form::Isolated => 0,
+                Form::Final => 1,
+                Form::Initial => 2,
+                Form::Medial => 3,
+                Form::None => return None,
+            };
+
+            let i = PRESENTATION_FORMS.binary_search_by_key(&(c as u16), |&(base, _)| base).ok()?;
+            let presentation = char::from_u32(PRESENTATION_FORMS[i].1[index] as u32).filter(|&p| p != '\0')?;
+
+            match self.get_glyph_id(presentation) {
+                0 => None,
+                id => Some(id),
+            }
+        };
+
+        for (i, (&c, &form)) in chars.iter().zip(forms.iter()).enumerate() {
+            if let Some(id) = form_glyph(c, form) {
+                buffer.glyphs[i].glyph_id = id;
+            }
+        }
+
+        // Lam followed directly by an alef becomes one ligature glyph
+        for i in (0..chars.len().saturating_sub(1)).rev() {
+            if chars[i] != LAM || !matches!(forms[i], Form::Initial | Form::Medial) {
+                continue;
+            }
+
+            let Some(&(_, isolated, final_form)) = LAM_ALEF_LIGATURES.iter().find(|&&(alef, _, _)| alef as u32 == chars[i + 1] as u32) else { continue };
+            let ligature = if forms[i] == Form::Initial { isolated } else { final_form };
+
+            let id = char::from_u32(ligature as u32).map(|l| self.get_glyph_id(l)).unwrap_or(0);
+            if id != 0 {
+                buffer.glyphs[i].glyph_id = id;
+                buffer.glyphs.remove(i + 1);
+            }
+        }
+    }
+}
+
+/// Generated from the Unicode 17.0 Joining_Type property for Arabic,
+/// Common and Inherited characters. Characters not listed are non-joining.
+const JOINING_TYPES: &[(u32, u32, JoiningType)] = &[
+    (0x00AD, 0x00AD, JoiningType::Transparent),
+    (0x0300, 0x036F, JoiningType::Transparent),
+    (0x0485, 0x0486, JoiningType::Transparent),
+    (0x0610, 0x061A, JoiningType::Transparent),
+    (0x061C, 0x061C, JoiningType::Transparent),
+    (0x0620, 0x0620, JoiningType::Dual),
+    (0x0622, 0x0625, JoiningType::Right),
+    (0x0626, 0x0626, JoiningType::Dual),
+    (0x0627, 0x0627, JoiningType::Right),
+    (0x0628, 0x0628, JoiningType::Dual),
+    (0x0629, 0x0629, JoiningType::Right),
+    (0x062A, 0x062E, JoiningType::Dual),
+    (0x062F, 0x0632, JoiningType::Right),
+    (0x0633, 0x063F, JoiningType::Dual),
+    (0x0640, 0x0640, JoiningType::Causing),
+    (0x0641, 0x0647, JoiningType::Dual),
+    (0x0648, 0x0648, JoiningType::Right),
+    (0x0649, 0x064A, JoiningType::Dual),
+    (0x064B, 0x065F, JoiningType::Transparent),
+    (0x066E, 0x066F, JoiningType::Dual),
+    (0x0670, 0x0670, JoiningType::Transparent),
+    (0x0671, 0x0673, JoiningType::Right),
+    (0x0675, 0x0677, JoiningType::Right),
+    (0x0678, 0x0687, JoiningType::Dual),
+    (0x0688, 0x0699, JoiningType::Right),
+    (0x069A, 0x06BF, JoiningType::Dual),
+    (0x06C0, 0x06C0, JoiningType::Right),
+    (0x06C1, 0x06C2, JoiningType::Dual),
+    (0x06C3, 0x06CB, JoiningType::Right),
+    (0x06CC, 0x06CC, JoiningType::Dual),
+    (0x06CD, 0x06CD, JoiningType::Right),
+    (0x06CE, 0x06CE, JoiningType::Dual),
+    (0x06CF, 0x06CF, JoiningType::Right),
+    (0x06D0, 0x06D1, JoiningType::Dual),
+    (0x06D2, 0x06D3, JoiningType::Right),
+    (0x06D5, 0x06D5, JoiningType::Right),
+    (0x06D6, 0x06DC, JoiningType::Transparent),
+    (0x06DF, 0x06E4, JoiningType::Transparent),
+    (0x06E7, 0x06E8, JoiningType::Transparent),
+    (0x06EA, 0x06ED, JoiningType::Transparent),
+    (0x06EE, 0x06EF, JoiningType::Right),
+    (0x06FA, 0x06FC, JoiningType::Dual),
+    (0x06FF, 0x06FF, JoiningType::Dual),
+    (0x0750, 0x0758, JoiningType::Dual),
+    (0x0759, 0x075B, JoiningType::Right),
+    (0x075C, 0x076A, JoiningType::Dual),
+    (0x076B, 0x076C, JoiningType::Right),
+    (0x076D, 0x0770, JoiningType::Dual),
+    (0x0771, 0x0771, JoiningType::Right),
+    (0x0772, 0x0772, JoiningType::Dual),
+    (0x0773, 0x0774, JoiningType::Right),
+    (0x0775, 0x0777, JoiningType::Dual),
+    (0x0778, 0x0779, JoiningType::Right),
+    (0x077A, 0x077F, JoiningType::Dual),
+    (0x0870, 0x0882, JoiningType::Right),
+    (0x0883, 0x0885, JoiningType::Causing),
+    (0x0886, 0x0886, JoiningType::Dual),
+    (0x0889, 0x088D, JoiningType::Dual),
+    (0x088E, 0x088E, JoiningType::Right),
+    (0x088F, 0x088F, JoiningType::Dual),
+    (0x0897, 0x089F, JoiningType::Transparent),
+    (0x08A0, 0x08A9, JoiningType::Dual),
+    (0x08AA, 0x08AC, JoiningType::Right),
+    (0x08AE, 0x08AE, JoiningType::Right),
+    (0x08AF, 0x08B0, JoiningType::Dual),
+    (0x08B1, 0x08B2, JoiningType::Right),
+    (0x08B3, 0x08B8, JoiningType::Dual),
+    (0x08B9, 0x08B9, JoiningType::Right),
+    (0x08BA, 0x08C8, JoiningType::Dual),
+    (0x08CA, 0x08E1, JoiningType::Transparent),
+    (0x08E3, 0x08FF, JoiningType::Transparent),
+    (0x0951, 0x0954, JoiningType::Transparent),
+    (0x1AB0, 0x1ADD, JoiningType::Transparent),
+    (0x1AE0, 0x1AEB, JoiningType::Transparent),
+    (0x1CD0, 0x1CD2, JoiningType::Transparent),
+    (0x1CD4, 0x1CE0, JoiningType::Transparent),
+    (0x1CE2, 0x1CE8, JoiningType::Transparent),
+    (0x1CED, 0x1CED, JoiningType::Transparent),
+    (0x1CF4, 0x1CF4, JoiningType::Transparent),
+    (0x1CF8, 0x1CF9, JoiningType::Transparent),
+    (0x1DC0, 0x1DFF, JoiningType::Transparent),
+    (0x200B, 0x200B, JoiningType::Transparent),
+    (0x200D, 0x200D, JoiningType::Causing),
+    (0x200E, 0x200F, JoiningType::Transparent),
+    (0x202A, 0x202E, JoiningType::Transparent),
+    (0x2060, 0x2064, JoiningType::Transparent),
+    (0x206A, 0x206F, JoiningType::Transparent),
+    (0x20D0, 0x20F0, JoiningType::Transparent),
+    (0x302A, 0x302D, JoiningType::Transparent),
+    (0x3099, 0x309A, JoiningType::Transparent),
+    (0xFE00, 0xFE0F, JoiningType::Transparent),
+    (0xFE20, 0xFE2D, JoiningType::Transparent),
+    (0xFEFF, 0xFEFF, JoiningType::Transparent),
+    (0xFFF9, 0xFFFB, JoiningType::Transparent),
+    (0x101FD, 0x101FD, JoiningType::Transparent),
+    (0x102E0, 0x102E0, JoiningType::Transparent),
+    (0x10EC2, 0x10EC2, JoiningType::Right),
+    (0x10EC3, 0x10EC4, JoiningType::Dual),
+    (0x10EC6, 0x10EC7, JoiningType::Dual),
+    (0x10EFA, 0x10EFF, JoiningType::Transparent),
+    (0x1133B, 0x1133B, JoiningType::Transparent),
+    (0x1BCA0, 0x1BCA3, JoiningType::Transparent),
+    (0x1CF00, 0x1CF2D, JoiningType::Transparent),
+    (0x1CF30, 0x1CF46, JoiningType::Transparent),
+    (0x1D167, 0x1D169, JoiningType::Transparent),
+    (0x1D173, 0x1D182, JoiningType::Transparent),
+    (0x1D185, 0x1D18B, JoiningType::Transparent),
+    (0x1D1AA, 0x1D1AD, JoiningType::Transparent),
+    (0xE0001, 0xE0001, JoiningType::Transparent),
+    (0xE0020, 0xE007F, JoiningType::Transparent),
+    (0xE0100, 0xE01EF, JoiningType::Transparent),
+];
+
+/// Presentation forms by base letter: isolated, final, initial, medial.
+const PRESENTATION_FORMS: &[(u16, [u16; 4])] = &[
+    (0x0621, [0xFE80, 0x0000, 0x0000, 0x0000]),
+    (0x0622, [0xFE81, 0xFE82, 0x0000, 0x0000]),
+    (0x0623, [0xFE83, 0xFE84, 0x0000, 0x0000]),
+    (0x0624, [0xFE85, 0xFE86, 0x0000, 0x0000]),
+    (0x0625, [0xFE87, 0xFE88, 0x0000, 0x0000]),
+    (0x0626, [0xFE89, 0xFE8A, 0xFE8B, 0xFE8C]),
+    (0x0627, [0xFE8D, 0xFE8E, 0x0000, 0x0000]),
+    (0x0628, [0xFE8F, 0xFE90, 0xFE91, 0xFE92]),
+    (0x0629, [0xFE93, 0xFE94, 0x0000, 0x0000]),
+    (0x062A, [0xFE95, 0xFE96, 0xFE97, 0xFE98]),
+    (0x062B, [0xFE99, 0xFE9A, 0xFE9B, 0xFE9C]),
+    (0x062C, [0xFE9D, 0xFE9E, 0xFE9F, 0xFEA0]),
+    (0x062D, [0xFEA1, 0xFEA2, 0xFEA3, 0xFEA4]),
+    (0x062E, [0xFEA5, 0xFEA6, 0xFEA7, 0xFEA8]),
+    (0x062F, [0xFEA9, 0xFEAA, 0x0000, 0x0000]),
+    (0x0630, [0xFEAB, 0xFEAC, 0x0000, 0x0000]),
+    (0x0631, [0xFEAD, 0xFEAE, 0x0000, 0x0000]),
+    (0x0632, [0xFEAF, 0xFEB0, 0x0000, 0x0000]),
+    (0x0633, [0xFEB1, 0xFEB2, 0xFEB3, 0xFEB4]),
+    (0x0634, [0xFEB5, 0xFEB6, 0xFEB7, 0xFEB8]),
+    (0x0635, [0xFEB9, 0xFEBA, 0xFEBB, 0xFEBC]),
+    (0x0636, [0xFEBD, 0xFEBE, 0xFEBF, 0xFEC0]),
+    (0x0637, [0xFEC1, 0xFEC2, 0xFEC3, 0xFEC4]),
+    (0x0638, [0xFEC5, 0xFEC6, 0xFEC7, 0xFEC8]),
+    (0x0639, [0xFEC9, 0xFECA, 0xFECB, 0xFECC]),
+    (0x063A, [0xFECD, 0xFECE, 0xFECF, 0xFED0]),
+    (0x0641, [0xFED1, 0xFED2, 0xFED3, 0xFED4]),
+    (0x0642, [0xFED5, 0xFED6, 0xFED7, 0xFED8]),
+    (0x0643, [0xFED9, 0xFEDA, 0xFEDB, 0xFEDC]),
+    (0x0644, [0xFEDD, 0xFEDE, 0xFEDF, 0xFEE0]),
+    (0x0645, [0xFEE1, 0xFEE2, 0xFEE3, 0xFEE4]),
+    (0x0646, [0xFEE5, 0xFEE6, 0xFEE7, 0xFEE8]),
+    (0x0647, [0xFEE9, 0xFEEA, 0xFEEB, 0xFEEC]),
+    (0x0648, [0xFEED, 0xFEEE, 0x0000, 0x0000]),
+    (0x0649, [0xFEEF, 0xFEF0, 0xFBE8, 0xFBE9]),
+    (0x064A, [0xFEF1, 0xFEF2, 0xFEF3, 0xFEF4]),
+    (0x0671, [0xFB50, 0xFB51, 0x0000, 0x0000]),
+    (0x0677, [0xFBDD, 0x0000, 0x0000, 0x0000]),
+    (0x0679, [0xFB66, 0xFB67, 0xFB68, 0xFB69]),
+    (0x067A, [0xFB5E, 0xFB5F, 0xFB60, 0xFB61]),
+    (0x067B, [0xFB52, 0xFB53, 0xFB54, 0xFB55]),
+    (0x067E, [0xFB56, 0xFB57, 0xFB58, 0xFB59]),
+    (0x067F, [0xFB62, 0xFB63, 0xFB64, 0xFB65]),
+    (0x0680, [0xFB5A, 0xFB5B, 0xFB5C, 0xFB5D]),
+    (0x0683, [0xFB76, 0xFB77, 0xFB78, 0xFB79]),
+    (0x0684, [0xFB72, 0xFB73, 0xFB74, 0xFB75]),
+    (0x0686, [0xFB7A, 0xFB7B, 0xFB7C, 0xFB7D]),
+    (0x0687, [0xFB7E, 0xFB7F, 0xFB80, 0xFB81]),
+    (0x0688, [0xFB88, 0xFB89, 0x0000, 0x0000]),
+    (0x068C, [0xFB84, 0xFB85, 0x0000, 0x0000]),
+    (0x068D, [0xFB82, 0xFB83, 0x0000, 0x0000]),
+    (0x068E, [0xFB86, 0xFB87, 0x0000, 0x0000]),
+    (0x0691, [0xFB8C, 0xFB8D, 0x0000, 0x0000]),
+    (0x0698, [0xFB8A, 0xFB8B, 0x0000, 0x0000]),
+    (0x06A4, [0xFB6A, 0xFB6B, 0xFB6C, 0xFB6D]),
+    (0x06A6, [0xFB6E, 0xFB6F, 0xFB70, 0xFB71]),
+    (0x06A9, [0xFB8E, 0xFB8F, 0xFB90, 0xFB91]),
+    (0x06AD, [0xFBD3, 0xFBD4, 0xFBD5, 0xFBD6]),
+    (0x06AF, [0xFB92, 0xFB93, 0xFB94, 0xFB95]),
+    (0x06B1, [0xFB9A, 0xFB9B, 0xFB9C, 0xFB9D]),
+    (0x06B3, [0xFB96, 0xFB97, 0xFB98, 0xFB99]),
+    (0x06BA, [0xFB9E, 0xFB9F, 0x0000, 0x0000]),
+    (0x06BB, [0xFBA0, 0xFBA1, 0xFBA2, 0xFBA3]),
+    (0x06BE, [0xFBAA, 0xFBAB, 0xFBAC, 0xFBAD]),
+    (0x06C0, [0xFBA4, 0xFBA5, 0x0000, 0x0000]),
+    (0x06C1, [0xFBA6, 0xFBA7, 0xFBA8, 0xFBA9]),
+    (0x06C5, [0xFBE0, 0xFBE1, 0x0000, 0x0000]),
+    (0x06C6, [0xFBD9, 0xFBDA, 0x0000, 0x0000]),
+    (0x06C7, [0xFBD7, 0xFBD8, 0x0000, 0x0000]),
+    (0x06C8, [0xFBDB, 0xFBDC, 0x0000, 0x0000]),
+    (0x06C9, [0xFBE2, 0xFBE3, 0x0000, 0x0000]),
+    (0x06CB, [0xFBDE, 0xFBDF, 0x0000, 0x0000]),
+    (0x06CC, [0xFBFC, 0xFBFD, 0xFBFE, 0xFBFF]),
+    (0x06D0, [0xFBE4, 0xFBE5, 0xFBE6, 0xFBE7]),
+    (0x06D2, [0xFBAE, 0xFBAF, 0x0000, 0x0000]),
+    (0x06D3, [0xFBB0, 0xFBB1, 0x0000, 0x0000]),
+];
+
+/// Lam-alef ligatures: alef variant, isolated form, final form.
+const LAM_ALEF_LIGATURES: &[(u16, u16, u16)] = &[
+    (0x0622, 0xFEF5, 0xFEF6),
+    (0x0623, 0xFEF7, 0xFEF8),
+    (0x0625, 0xFEF9, 0xFEFA),
+    (0x0627, 0xFEFB, 0xFEFC),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEH: char = '\u{0628}';
+    const TEH: char = '\u{062A}';
+    const ALEF: char = '\u{0627}';
+    const DAL: char = '\u{062F}';
+    const FATHA: char = '\u{064E}';
+    const ZWNJ: char = '\u{200C}';
+    const ZWJ: char = '\u{200D}';
+
+    #[test]
+    fn dual_joining_letters_join_on_both_sides() {
+        assert_eq!(joining_forms(&[BEH]), [Form::Isolated]);
+        assert_eq!(joining_forms(&[BEH, TEH, BEH]), [Form::Initial, Form::Medial, Form::Final]);
+    }
+
+    #[test]
+    fn right_joining_letters_break_the_join_after_them() {
+        assert_eq!(joining_forms(&[BEH, ALEF, BEH]), [Form::Initial, Form::Final, Form::Isolated]);
+        assert_eq!(joining_forms(&[DAL, BEH]), [Form::Isolated, Form::Isolated]);
+        assert_eq!(joining_forms(&[BEH, DAL, TEH, BEH]), [Form::Initial, Form::Final, Form::Initial, Form::Final]);
+    }
+
+    #[test]
+    fn transparent_marks_are_skipped() {
+        assert_eq!(joining_forms(&[BEH, FATHA, BEH]), [Form::Initial, Form::None, Form::Final]);
+        assert_eq!(joining_forms(&[BEH, FATHA, FATHA, TEH, FATHA]), [Form::Initial, Form::None, Form::None, Form::Final, Form::None]);
+    }
+
+    #[test]
+    fn zwj_causes_joining_and_zwnj_prevents_it() {
+        assert_eq!(joining_forms(&[BEH, ZWJ]), [Form::Initial, Form::None]);
+        assert_eq!(joining_forms(&[ZWJ, BEH]), [Form::None, Form::Final]);
+        assert_eq!(joining_forms(&[ZWJ, BEH, ZWJ]), [Form::None, Form::Medial, Form::None]);
+        assert_eq!(joining_forms(&[BEH, ZWNJ, BEH]), [Form::Isolated, Form::None, Form::Isolated]);
+    }
+
+    #[test]
+    fn lam_alef_takes_initial_and_final_forms() {
+        assert_eq!(joining_forms(&[LAM, ALEF]), [Form::Initial, Form::Final]);
+        assert_eq!(joining_forms(&[BEH, LAM, ALEF]), [Form::Initial, Form::Medial, Form::Final]);
+
+        let ligature = LAM_ALEF_LIGATURES.iter().find(|&&(alef, _, _)| alef as u32 == ALEF as u32);
+        assert_eq!(ligature, Some(&(0x0627, 0xFEFB, 0xFEFC)));
+    }
+}
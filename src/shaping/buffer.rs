@@ -0,0 +1,146 @@
+use crate::Vec;
+
+/// Mask carried by every glyph; features applied to the whole run use it.
+pub(crate) const GLOBAL_MASK: u32 = 0x0000_0001;
+
+/// Mask of right-to-left glyphs that have no mirrored counterpart in the
+/// cmap and are left to the `rtlm` feature instead.
+pub(crate) const RTLM_MASK: u32 = 0x8000_0000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GlyphInfo {
+    pub glyph_id: u32,
+    /// Index of the first input element this glyph was produced from.
+    pub cluster: usize,
+    pub(crate) mask: u32,
+    pub(crate) lig_id: u8,
+    pub(crate) lig_component: u8,
+    /// Where the glyph sits within its syllable, used by the Indic shaper.
+    pub(crate) syllable_position: u8,
+}
+
+impl GlyphInfo {
+    pub fn new(glyph_id: u32, cluster: usize) -> Self {
+        GlyphInfo {
+            glyph_id,
+            cluster,
+            mask: GLOBAL_MASK,
+            lig_id: 0,
+            lig_component: 0,
+            syllable_position: 0,
+        }
+    }
+}
+
+/// Placement of a glyph in font units, filled in by GPOS.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GlyphPosition {
+    pub x_advance: i32,
+    pub y_advance: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+    /// Relative index of the glyph this one is attached to, 0 if none.
+    pub(crate) attach_chain: i16,
+    pub(crate) attach_type: u8,
+}
+
+impl GlyphPosition {
+    /// Converts the position to pixels using a scale from
+    /// [`TrueTypeFont::scale_for_size`](crate::TrueTypeFont::scale_for_size).
+    pub fn scaled(&self, scale: f32) -> ScaledPosition {
+        ScaledPosition {
+            x_advance: self.x_advance as f32 * scale,
+            y_advance: self.y_advance as f32 * scale,
+            x_offset: self.x_offset as f32 * scale,
+            y_offset: self.y_offset as f32 * scale,
+        }
+    }
+}
+
+/// Placement of a glyph in pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ScaledPosition {
+    pub x_advance: f32,
+    pub y_advance: f32,
+    pub x_offset: f32,
+    pub y_offset: f32,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+    /// Vertical lines, as in Japanese or Chinese vertical writing. Glyphs
+    /// advance downwards by their vertical advance and are positioned from
+    /// their vertical origin.
+    TopToBottom,
+}
+
+impl Direction {
+    pub fn is_rtl(self) -> bool {
+        self == Direction::RightToLeft
+    }
+
+    pub fn is_vertical(self) -> bool {
+        self == Direction::TopToBottom
+    }
+}
+
+/// A run of glyphs in logical order that substitution lookups rewrite in
+/// place and positioning lookups annotate with `positions`.
+#[derive(Clone, Debug, Default)]
+pub struct GlyphBuffer {
+    pub glyphs: Vec<GlyphInfo>,
+    pub positions: Vec<GlyphPosition>,
+    pub direction: Direction,
+    pub(crate) next_lig_id: u8,
+}
+
+impl GlyphBuffer {
+    pub fn new() -> Self {
+        GlyphBuffer {
+            glyphs: Vec::new(),
+            positions: Vec::new(),
+            direction: Direction::LeftToRight,
+            next_lig_id: 0,
+        }
+    }
+
+    /// Builds a buffer from glyph ids, using each glyph's index as its cluster.
+    pub fn from_glyphs(glyph_ids: &[u32]) -> Self {
+        let mut buffer = GlyphBuffer::new();
+        for (cluster, &id) in glyph_ids.iter().enumerate() {
+            buffer.push(id, cluster);
+        }
+        buffer
+    }
+
+    pub fn push(&mut self, glyph_id: u32, cluster: usize) {
+        self.glyphs.push(GlyphInfo::new(glyph_id, cluster));
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    pub fn scaled_positions(&self, scale: f32) -> Vec<ScaledPosition> {
+        self.positions.iter().map(|p| p.scaled(scale)).collect()
+    }
+
+    pub fn glyph_ids(&self) -> Vec<u32> {
+        self.glyphs.iter().map(|g| g.glyph_id).collect()
+    }
+
+    pub(crate) fn allocate_lig_id(&mut self) -> u8 {
+        self.next_lig_id = self.next_lig_id.wrapping_add(1);
+        if self.next_lig_id == 0 {
+            self.next_lig_id = 1;
+        }
+        self.next_lig_id
+    }
+}
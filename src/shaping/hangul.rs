@@ -0,0 +1,212 @@
+use crate::font::TrueTypeFont;
+use crate::shaping::buffer::GLOBAL_MASK;
+use crate::shaping::{shaped_glyphs, Feature, GlyphBuffer, GlyphInfo, ShapedGlyph, ShapingOptions};
+use crate::Vec;
+
+const LJMO_MASK: u32 = 1 << 1;
+const VJMO_MASK: u32 = 1 << 2;
+const TJMO_MASK: u32 = 1 << 3;
+/// Choseong and jungseong fillers, which only stand in for a missing jamo.
+const FILLER_MASK: u32 = 1 << 4;
+
+const L_FILLER: char = '\u{115F}';
+const V_FILLER: char = '\u{1160}';
+
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const S_COUNT: u32 = L_COUNT * V_COUNT * T_COUNT;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Jamo {
+    Leading,
+    Vowel,
+    Trailing,
+    /// Precomposed syllable without a trailing consonant.
+    Lv,
+    /// Precomposed syllable with a trailing consonant.
+    Lvt,
+    None,
+}
+
+fn jamo(c: char) -> Jamo {
+    match c as u32 {
+        0x1100..=0x115F | 0xA960..=0xA97C => Jamo::Leading,
+        0x1160..=0x11A7 | 0xD7B0..=0xD7C6 => Jamo::Vowel,
+        0x11A8..=0x11FF | 0xD7CB..=0xD7FB => Jamo::Trailing,
+        cp @ S_BASE..=0xD7A3 if (cp - S_BASE).is_multiple_of(T_COUNT) => Jamo::Lv,
+        S_BASE..=0xD7A3 => Jamo::Lvt,
+        _ => Jamo::None,
+    }
+}
+
+/// Composes modern jamo into a precomposed syllable; `t` may be absent.
+fn compose(l: char, v: char, t: Option<char>) -> Option<char> {
+    let l = (l as u32).checked_sub(L_BASE).filter(|&l| l < L_COUNT)?;
+    let v = (v as u32).checked_sub(V_BASE).filter(|&v| v < V_COUNT)?;
+    let t = match t {
+        Some(t) => (t as u32).checked_sub(T_BASE).filter(|&t| t > 0 && t < T_COUNT)?,
+        None => 0,
+    };
+
+    char::from_u32(S_BASE + (l * V_COUNT + v) * T_COUNT + t)
+}
+
+/// Splits a precomposed syllable into its leading, vowel and optional
+/// trailing jamo.
+fn decompose(s: char) -> Option<(char, char, Option<char>)> {
+    let index = (s as u32).checked_sub(S_BASE).filter(|&i| i < S_COUNT)?;
+    let l = char::from_u32(L_BASE + index / (V_COUNT * T_COUNT))?;
+    let v = char::from_u32(V_BASE + (index % (V_COUNT * T_COUNT)) / T_COUNT)?;
+    let t = match index % T_COUNT {
+        0 => None,
+        t => char::from_u32(T_BASE + t),
+    };
+
+    Some((l, v, t))
+}
+
+impl TrueTypeFont {
+    /// Shapes Korean text: conjoining jamo are composed into syllables the
+    /// font has glyphs for, and syllables the font lacks are decomposed so
+    /// the `ljmo`/`vjmo`/`tjmo` features can assemble them from jamo.
+    pub(crate) fn shape_hangul(&self, text: &str, options: &ShapingOptions) -> Vec<ShapedGlyph> {
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let mut buffer = GlyphBuffer::new();
+        buffer.direction = options.direction;
+        let has = |c: char| self.get_glyph_id(c) != 0;
+
+        let mut i = 0;
+        while i < chars.len() {
+            let (cluster, c) = chars[i];
+            let kind = |n: usize| chars.get(n).map_or(Jamo::None, |&(_, c)| jamo(c));
+
+            match jamo(c) {
+                Jamo::Leading => {
+                    let mut end = i + 1;
+                    while kind(end) == Jamo::Leading {
+                        end += 1;
+                    }
+                    while kind(end) == Jamo::Vowel {
+                        end += 1;
+                    }
+                    while kind(end) == Jamo::Trailing {
+                        end += 1;
+                    }
+
+                    let run: Vec<char> = chars[i..end].iter().map(|&(_, c)| c).collect();
+                    let composed = match run.len() {
+                        2 => compose(run[0], run[1], None),
+                        3 => compose(run[0], run[1], Some(run[2])),
+                        _ => None,
+                    };
+
+                    match composed {
+                        Some(s) if has(s) => buffer.push(self.get_glyph_id(s), cluster),
+                        _ => self.push_jamo(&mut buffer, &run, cluster),
+                    }
+                    i = end;
+                }
+                Jamo::Lv | Jamo::Lvt => {
+                    let mut end = i + 1;
+                    while kind(end) == Jamo::Trailing {
+                        end += 1;
+                    }
+
+                    // An LV syllable followed by one trailing jamo composes to LVT
+                    let trailing = &chars[i + 1..end];
+                    let composed = match (jamo(c), trailing) {
+                        (Jamo::Lv, [(_, t)]) => decompose(c).and_then(|(l, v, _)| compose(l, v, Some(*t))),
+                        (_, []) => Some(c),
+                        _ => None,
+                    };
+
+                    match composed {
+                        Some(s) if has(s) => buffer.push(self.get_glyph_id(s), cluster),
+                        _ => {
+                            let mut run = Vec::new();
+                            if let Some((l, v, t)) = decompose(c) {
+                                run.extend([l, v]);
+                                run.extend(t);
+                            }
+                            run.extend(trailing.iter().map(|&(_, t)| t));
+
+                            if run.iter().all(|&j| has(j)) {
+                                self.push_jamo(&mut buffer, &run, cluster);
+                            } else {
+                                buffer.push(self.get_glyph_id(c), cluster);
+                                for &(t_cluster, t) in trailing {
+                                    buffer.push(self.get_glyph_id(t), t_cluster);
+                                }
+                            }
+                        }
+                    }
+                    i = end;
+                }
+                _ => {
+                    buffer.glyphs.push(GlyphInfo {
+                        mask: GLOBAL_MASK | filler_mask(c),
+                        ..GlyphInfo::new(self.get_glyph_id(c), cluster)
+                    });
+                    i += 1;
+                }
+            }
+        }
+
+        let extra = [
+            (Feature::on(*b"ljmo"), LJMO_MASK),
+            (Feature::on(*b"vjmo"), VJMO_MASK),
+            (Feature::on(*b"tjmo"), TJMO_MASK),
+        ];
+
+        self.apply_variation_selectors(&mut buffer, text);
+        self.substitute_default(&mut buffer, options, &extra);
+        self.position_default(&mut buffer, options, &[]);
+        self.hide_default_ignorables(&mut buffer, text);
+        self.hide_fillers(&mut buffer);
+
+        shaped_glyphs(buffer)
+    }
+
+    /// Fillers are default ignorables, but they share a cluster with the
+    /// jamo they pad, so they are hidden glyph by glyph instead of through
+    /// `hide_default_ignorables`. Fillers a substitution replaced stay.
+    fn hide_fillers(&self, buffer: &mut GlyphBuffer) {
+        let space = self.get_glyph_id(' ');
+        let fillers = [self.get_glyph_id(L_FILLER), self.get_glyph_id(V_FILLER)];
+
+        for (info, pos) in buffer.glyphs.iter_mut().zip(buffer.positions.iter_mut()) {
+            if info.mask & FILLER_MASK != 0 && fillers.contains(&info.glyph_id) {
+                info.glyph_id = space;
+                pos.x_advance = 0;
+                pos.y_advance = 0;
+            }
+        }
+    }
+
+    /// Pushes a syllable as individual jamo sharing one cluster, each masked
+    /// for the jamo feature of its kind.
+    fn push_jamo(&self, buffer: &mut GlyphBuffer, run: &[char], cluster: usize) {
+        for &c in run {
+            let mask = match jamo(c) {
+                Jamo::Leading => LJMO_MASK,
+                Jamo::Vowel => VJMO_MASK,
+                Jamo::Trailing => TJMO_MASK,
+                _ => 0,
+            };
+
+            buffer.glyphs.push(GlyphInfo {
+                mask: GLOBAL_MASK | mask | filler_mask(c),
+                ..GlyphInfo::new(self.get_glyph_id(c), cluster)
+            });
+        }
+    }
+}
+
+fn filler_mask(c: char) -> u32 {
+    if c == L_FILLER || c == V_FILLER { FILLER_MASK } else { 0 }
+}
@@ -0,0 +1,566 @@
+use crate::font::TrueTypeFont;
+use crate::shaping::buffer::GLOBAL_MASK;
+use crate::tables::cmap::is_variation_selector;
+use crate::shaping::{shaped_glyphs, Feature, GlyphBuffer, GlyphInfo, ShapedGlyph, ShapingOptions, Tag};
+use crate::Vec;
+
+const RPHF_MASK: u32 = 1 << 1;
+const HALF_MASK: u32 = 1 << 2;
+const BLWF_MASK: u32 = 1 << 3;
+const POST_MASK: u32 = 1 << 4;
+
+// Syllable positions, in the order glyphs end up after reordering
+const POS_NONE: u8 = 0;
+const POS_RA_TO_BECOME_REPH: u8 = 1;
+const POS_PRE_M: u8 = 2;
+const POS_PRE_C: u8 = 3;
+const POS_BASE_C: u8 = 4;
+const POS_POST_C: u8 = 5;
+const POS_BELOW_M: u8 = 6;
+const POS_ABOVE_M: u8 = 7;
+const POS_POST_M: u8 = 8;
+const POS_SMVD: u8 = 9;
+const POS_JOINER: u8 = 10;
+
+const DOTTED_CIRCLE: char = '\u{25CC}';
+
+/// Features applied one stage at a time before final reordering, each
+/// restricted to the glyphs carrying its mask.
+const BASIC_FEATURES: [(Tag, u32); 11] = [
+    (*b"nukt", GLOBAL_MASK),
+    (*b"akhn", GLOBAL_MASK),
+    (*b"rphf", RPHF_MASK),
+    (*b"rkrf", GLOBAL_MASK),
+    (*b"pref", POST_MASK),
+    (*b"blwf", BLWF_MASK),
+    (*b"abvf", POST_MASK),
+    (*b"half", HALF_MASK),
+    (*b"pstf", POST_MASK),
+    (*b"vatu", GLOBAL_MASK),
+    (*b"cjct", GLOBAL_MASK),
+];
+
+/// Features applied together once syllables are in their final order.
+const PRESENTATION_FEATURES: [Tag; 10] = [
+    *b"pres", *b"abvs", *b"blws", *b"psts", *b"haln",
+    *b"rlig", *b"rclt", *b"calt", *b"liga", *b"clig",
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Script {
+    Devanagari,
+    Bengali,
+    Tamil,
+}
+
+impl Script {
+    fn from_tag(tag: Tag) -> Option<Self> {
+        match &tag {
+            b"deva" | b"dev2" => Some(Script::Devanagari),
+            b"beng" | b"bng2" => Some(Script::Bengali),
+            b"taml" | b"tml2" => Some(Script::Tamil),
+            _ => None,
+        }
+    }
+
+    /// The OpenType 1.5 ("new") and original script tags.
+    fn tags(self) -> (Tag, Tag) {
+        match self {
+            Script::Devanagari => (*b"dev2", *b"deva"),
+            Script::Bengali => (*b"bng2", *b"beng"),
+            Script::Tamil => (*b"tml2", *b"taml"),
+        }
+    }
+
+    fn has_reph(self) -> bool {
+        self != Script::Tamil
+    }
+
+    fn halant(self) -> char {
+        match self {
+            Script::Devanagari => '\u{094D}',
+            Script::Bengali => '\u{09CD}',
+            Script::Tamil => '\u{0BCD}',
+        }
+    }
+
+    /// The last syllable position a formed reph is moved behind: before the
+    /// matras in Devanagari, after the below-base forms in Bengali and after
+    /// everything but the vowel modifiers in Tamil.
+    fn reph_position(self) -> u8 {
+        match self {
+            Script::Devanagari => POS_POST_C,
+            Script::Bengali => POS_BELOW_M,
+            Script::Tamil => POS_POST_M,
+        }
+    }
+
+    /// Consonants that take a below or post-base form after a halant, so
+    /// they are skipped when looking for the base consonant.
+    fn has_post_form(self, c: char) -> bool {
+        match self {
+            Script::Devanagari => c == '\u{0930}',
+            Script::Bengali => matches!(c, '\u{09AC}' | '\u{09AF}' | '\u{09B0}' | '\u{09F0}'),
+            Script::Tamil => false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Category {
+    Other,
+    Consonant,
+    Ra,
+    Vowel,
+    Matra,
+    Nukta,
+    Halant,
+    VowelModifier,
+    Zwj,
+    Zwnj,
+    Placeholder,
+}
+
+impl Category {
+    fn is_base(self) -> bool {
+        matches!(self, Category::Consonant | Category::Ra | Category::Vowel | Category::Placeholder)
+    }
+
+    fn is_joiner(self) -> bool {
+        matches!(self, Category::Zwj | Category::Zwnj)
+    }
+}
+
+fn category(c: char) -> Category {
+    let cp = c as u32;
+    let block = cp & !0x7F;
+
+    match cp {
+        0x200C => return Category::Zwnj,
+        0x200D => return Category::Zwj,
+        0x00A0 | 0x25CC => return Category::Placeholder,
+        0x0930 | 0x09B0 | 0x09F0 => return Category::Ra,
+        0x09CE | 0x09F1 => return Category::Consonant,
+        _ => {}
+    }
+
+    if !matches!(block, 0x0900 | 0x0980 | 0x0B80) {
+        return Category::Other;
+    }
+
+    match cp & 0x7F {
+        0x00..=0x03 | 0x51..=0x54 => Category::VowelModifier,
+        0x04..=0x14 | 0x60 | 0x61 => Category::Vowel,
+        0x15..=0x39 | 0x58..=0x5F => Category::Consonant,
+        0x3A | 0x3B | 0x3E..=0x4C | 0x4E | 0x4F | 0x55..=0x57 | 0x62 | 0x63 => Category::Matra,
+        0x3C => Category::Nukta,
+        0x4D => Category::Halant,
+        0x72..=0x77 if block == 0x0900 => Category::Vowel,
+        0x78..=0x7F if block == 0x0900 => Category::Consonant,
+        _ => Category::Other,
+    }
+}
+
+fn matra_position(c: char) -> u8 {
+    match c as u32 {
+        0x093F | 0x094E | 0x09BF | 0x09C7 | 0x09C8 | 0x0BC6..=0x0BC8 => POS_PRE_M,
+        0x0941..=0x0944 | 0x0956 | 0x0957 | 0x0962 | 0x0963 | 0x09C1..=0x09C4 | 0x09E2 | 0x09E3 => POS_BELOW_M,
+        0x093A | 0x0945..=0x0948 | 0x0955 | 0x0BC0 => POS_ABOVE_M,
+        _ => POS_POST_M,
+    }
+}
+
+/// Two part vowel signs are split so the pre-base half can be reordered.
+fn split_matra(c: char) -> Option<[char; 2]> {
+    match c {
+        '\u{09CB}' => Some(['\u{09C7}', '\u{09BE}']),
+        '\u{09CC}' => Some(['\u{09C7}', '\u{09D7}']),
+        '\u{0BCA}' => Some(['\u{0BC6}', '\u{0BBE}']),
+        '\u{0BCB}' => Some(['\u{0BC7}', '\u{0BBE}']),
+        '\u{0BCC}' => Some(['\u{0BC6}', '\u{0BD7}']),
+        _ => None,
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Entry {
+    c: char,
+    cluster: usize,
+    category: Category,
+    position: u8,
+    mask: u32,
+    /// Glyph chosen by a following variation selector.
+    variant: Option<u32>,
+}
+
+impl Entry {
+    fn new(c: char, cluster: usize) -> Self {
+        Entry { c, cluster, category: category(c), position: POS_NONE, mask: GLOBAL_MASK, variant: None }
+    }
+}
+
+/// Finds the end of the syllable starting at `start`:
+/// `(C N? H ZW?)* C N? M* (H ZW?)? SM*` for consonant and vowel syllables,
+/// and a run of matras and modifiers for broken ones.
+fn syllable_end(entries: &[Entry], start: usize) -> usize {
+    let at = |i: usize| entries.get(i).map(|e| e.category).unwrap_or(Category::Other);
+    let mut i = start;
+
+    match at(i) {
+        category if category.is_base() => loop {
+            i += 1;
+            if at(i) == Category::Nukta {
+                i += 1;
+            }
+
+            if at(i) != Category::Halant {
+                break;
+            }
+
+            let mut next = i + 1;
+            if at(next).is_joiner() {
+                next += 1;
+            }
+
+            if !matches!(at(next), Category::Consonant | Category::Ra) {
+                break;
+            }
+            i = next;
+        },
+        Category::Matra | Category::Nukta | Category::Halant | Category::VowelModifier => {}
+        _ => return start + 1,
+    }
+
+    while at(i) == Category::Matra {
+        i += 1;
+        if at(i) == Category::Nukta {
+            i += 1;
+        }
+    }
+
+    if at(i) == Category::Halant {
+        i += 1;
+        if at(i).is_joiner() {
+            i += 1;
+        }
+    }
+
+    while at(i) == Category::VowelModifier {
+        i += 1;
+    }
+
+    i.max(start + 1)
+}
+
+/// Initial reordering of one syllable: assigns positions and feature masks
+/// and moves pre-base matras in front of the consonant cluster.
+fn reorder_syllable(syllable: &mut Vec<Entry>, script: Script, has_rphf: bool) {
+    let limit = syllable.iter()
+        .position(|e| matches!(e.category, Category::Matra | Category::VowelModifier))
+        .unwrap_or(syllable.len());
+
+    let has_reph = script.has_reph() && has_rphf && limit >= 3
+        && syllable[0].category == Category::Ra
+        && syllable[1].category == Category::Halant
+        && syllable[2].category != Category::Zwj
+        && syllable[2..limit].iter().any(|e| e.category.is_base());
+    let pre_start = if has_reph { 2 } else { 0 };
+
+    let candidates: Vec<usize> = (pre_start..limit).filter(|&i| syllable[i].category.is_base()).collect();
+    let Some(&first) = candidates.first() else { return };
+
+    let base = candidates.iter().rev()
+        .find(|&&i| {
+            i == first || !script.has_post_form(syllable[i].c) || syllable[i - 1].category != Category::Halant
+        })
+        .copied()
+        .unwrap_or(first);
+
+    for (i, entry) in syllable.iter_mut().enumerate() {
+        entry.position = if entry.category.is_joiner() {
+            POS_JOINER
+        } else if i < pre_start {
+            POS_RA_TO_BECOME_REPH
+        } else if i < base {
+            POS_PRE_C
+        } else if i == base {
+            POS_BASE_C
+        } else if i < limit {
+            POS_POST_C
+        } else {
+            match entry.category {
+                Category::Matra => matra_position(entry.c),
+                Category::VowelModifier => POS_SMVD,
+                _ => POS_POST_C,
+            }
+        };
+    }
+
+    if has_reph {
+        syllable[0].mask |= RPHF_MASK;
+        syllable[1].mask |= RPHF_MASK;
+    }
+
+    // A ZWNJ after the halant asks for an explicit halant instead of a half form
+    for i in pre_start..base {
+        let halant = (i..base).find(|&k| syllable[k].category == Category::Halant);
+        let blocked = halant.is_some_and(|k| syllable.get(k + 1).is_some_and(|e| e.category == Category::Zwnj));
+        if !blocked {
+            syllable[i].mask |= HALF_MASK | BLWF_MASK;
+        }
+    }
+
+    for entry in &mut syllable[base + 1..limit] {
+        entry.mask |= BLWF_MASK | POST_MASK;
+    }
+
+    let mut moved = 0;
+    for i in limit..syllable.len() {
+        if syllable[i].position == POS_PRE_M {
+            let matra = syllable.remove(i);
+            syllable.insert(pre_start + moved, matra);
+            moved += 1;
+        }
+    }
+}
+
+impl TrueTypeFont {
+    /// Shapes Devanagari, Bengali and Tamil text syllable by syllable:
+    /// syllables are reordered, run through the basic forming features one
+    /// at a time, have their reph moved into place and then go through the
+    /// presentation features and GPOS.
+    pub(crate) fn shape_indic(&self, text: &str, options: &ShapingOptions) -> Vec<ShapedGlyph> {
+        let Some(script) = Script::from_tag(options.script) else { return Vec::new() };
+
+        let (new_tag, old_tag) = script.tags();
+        let mut options = options.clone();
+        options.script = if self.gsub.has_script(new_tag) || self.gpos.has_script(new_tag) { new_tag } else { old_tag };
+
+        let enabled = |tag: Tag| options.is_enabled(tag) && self.gsub.has_feature(options.script, options.language, tag);
+        let has_rphf = enabled(*b"rphf");
+
+        let mut entries = Vec::new();
+        for (cluster, c) in text.char_indices() {
+            match split_matra(c) {
+                Some(parts) => entries.extend(parts.iter().map(|&part| Entry::new(part, cluster))),
+                None => entries.push(Entry::new(c, cluster)),
+            }
+        }
+
+        for i in 1..entries.len() {
+            if is_variation_selector(entries[i].c) {
+                entries[i - 1].variant = self.glyph_for_variation(entries[i - 1].c, entries[i].c);
+            }
+        }
+
+        let mut buffer = GlyphBuffer::new();
+        buffer.direction = options.direction;
+        let mut start = 0;
+
+        while start < entries.len() {
+            let end = syllable_end(&entries, start);
+            let mut syllable = entries[start..end].to_vec();
+            let cluster = syllable[0].cluster;
+
+            let broken = matches!(syllable[0].category, Category::Matra | Category::Nukta | Category::Halant | Category::VowelModifier);
+            if broken && self.get_glyph_id(DOTTED_CIRCLE) != 0 {
+                syllable.insert(0, Entry::new(DOTTED_CIRCLE, cluster));
+            }
+
+            if syllable[0].category.is_base() {
+                reorder_syllable(&mut syllable, script, has_rphf);
+            }
+
+            // Reordered glyphs share the cluster of the whole syllable
+            for entry in syllable {
+                buffer.glyphs.push(GlyphInfo {
+                    mask: entry.mask,
+                    syllable_position: entry.position,
+                    ..GlyphInfo::new(entry.variant.unwrap_or_else(|| self.get_glyph_id(entry.c)), cluster)
+                });
+            }
+
+            start = end;
+        }
+
+        let stage = |buffer: &mut GlyphBuffer, features: &[(Feature, u32)]| {
+            let plan = self.gsub.plan(options.script, options.language, features);
+            self.apply_gsub_plan(buffer, &plan);
+        };
+
+        let initial: Vec<(Feature, u32)> = [*b"locl", *b"ccmp"].iter()
+            .filter(|&&tag| options.is_enabled(tag))
+            .map(|&tag| (Feature::on(tag), GLOBAL_MASK))
+            .collect();
+        stage(&mut buffer, &initial);
+
+        for &(tag, mask) in &BASIC_FEATURES {
+            if enabled(tag) {
+                stage(&mut buffer, &[(Feature::on(tag), mask)]);
+            }
+        }
+
+        final_reordering(&mut buffer, script, self.get_glyph_id(script.halant()));
+
+        stage(&mut buffer, &options.feature_list(&PRESENTATION_FEATURES));
+
+        let extra: Vec<(Feature, u32)> = [*b"dist"].iter()
+            .filter(|&&tag| options.is_enabled(tag))
+            .map(|&tag| (Feature::on(tag), GLOBAL_MASK))
+            .collect();
+        self.position_default(&mut buffer, &options, &extra);
+
+        let space = self.get_glyph_id(' ');
+        for (info, pos) in buffer.glyphs.iter_mut().zip(buffer.positions.iter_mut()) {
+            if info.syllable_position == POS_JOINER {
+                info.glyph_id = space;
+                pos.x_advance = 0;
+            }
+        }
+        self.hide_default_ignorables(&mut buffer, text);
+
+        shaped_glyphs(buffer)
+    }
+}
+
+/// Moves pre-base matras and a formed reph to their final places in each
+/// syllable. `halant` is the nominal halant glyph, which only survives the
+/// basic features where no half form was made.
+fn final_reordering(buffer: &mut GlyphBuffer, script: Script, halant: u32) {
+    let mut start = 0;
+
+    while start < buffer.glyphs.len() {
+        let cluster = buffer.glyphs[start].cluster;
+        let end = buffer.glyphs[start..].iter()
+            .position(|g| g.cluster != cluster)
+            .map_or(buffer.glyphs.len(), |n| start + n);
+
+        if halant != 0 {
+            reorder_pre_base_matras(&mut buffer.glyphs[start..end], halant);
+        }
+
+        if buffer.glyphs[start].syllable_position == POS_RA_TO_BECOME_REPH {
+            // An unformed reph leaves its halant behind
+            let formed = end - start < 2 || buffer.glyphs[start + 1].syllable_position != POS_RA_TO_BECOME_REPH;
+
+            if formed {
+                let target = (start + 1..end).rev()
+                    .find(|&i| buffer.glyphs[i].syllable_position <= script.reph_position());
+
+                if let Some(target) = target {
+                    let reph = buffer.glyphs.remove(start);
+                    buffer.glyphs.insert(target, reph);
+                }
+            } else {
+                for glyph in &mut buffer.glyphs[start..start + 2] {
+                    glyph.syllable_position = POS_PRE_C;
+                }
+            }
+        }
+
+        start = end;
+    }
+}
+
+/// Pre-base matras were put in front of the whole consonant cluster. When a
+/// consonant before the base kept an explicit halant, because the font has
+/// no half form or a ZWNJ blocked it, the matras move after that halant and
+/// any joiner following it, next to the consonant they belong to.
+fn reorder_pre_base_matras(syllable: &mut [GlyphInfo], halant: u32) {
+    let Some(first) = syllable.iter().position(|g| g.syllable_position == POS_PRE_M) else { return };
+    let count = syllable[first..].iter().take_while(|g| g.syllable_position == POS_PRE_M).count();
+
+    let base = syllable.iter()
+        .position(|g| (POS_BASE_C..POS_JOINER).contains(&g.syllable_position))
+        .unwrap_or(syllable.len());
+
+    let Some(mut target) = (first + count..base).rev().find(|&i| syllable[i].glyph_id == halant) else { return };
+    while syllable.get(target + 1).is_some_and(|g| g.syllable_position == POS_JOINER) {
+        target += 1;
+    }
+
+    syllable[first..=target].rotate_left(count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALANT: u32 = 9;
+
+    /// Runs initial reordering on `text` as one syllable and maps each
+    /// character to a glyph id by its place in `text`.
+    fn reordered(text: &str) -> GlyphBuffer {
+        let chars: Vec<char> = text.chars().collect();
+        let mut syllable: Vec<Entry> = chars.iter().map(|&c| Entry::new(c, 0)).collect();
+        reorder_syllable(&mut syllable, Script::Devanagari, true);
+
+        let mut buffer = GlyphBuffer::new();
+        for entry in syllable {
+            let glyph_id = match entry.category {
+                Category::Halant => HALANT,
+                _ => chars.iter().position(|&c| c == entry.c).unwrap() as u32 + 100,
+            };
+            buffer.glyphs.push(GlyphInfo { syllable_position: entry.position, ..GlyphInfo::new(glyph_id, 0) });
+        }
+        buffer
+    }
+
+    /// Stands in for `rphf`, ligating the leading ra and halant into one glyph.
+    fn form_reph(buffer: &mut GlyphBuffer) {
+        assert_eq!(buffer.glyphs[0].syllable_position, POS_RA_TO_BECOME_REPH);
+        buffer.glyphs.remove(1);
+        buffer.glyphs[0].glyph_id = 1;
+    }
+
+    fn glyph_ids(buffer: &GlyphBuffer) -> Vec<u32> {
+        buffer.glyphs.iter().map(|g| g.glyph_id).collect()
+    }
+
+    #[test]
+    fn reph_follows_base_after_pre_base_matra() {
+        // र्कि: the i matra ends up first and the reph after ka
+        let mut buffer = reordered("\u{0930}\u{094D}\u{0915}\u{093F}");
+        assert_eq!(glyph_ids(&buffer), [100, HALANT, 103, 102]);
+
+        form_reph(&mut buffer);
+        final_reordering(&mut buffer, Script::Devanagari, HALANT);
+        assert_eq!(glyph_ids(&buffer), [103, 102, 1]);
+    }
+
+    #[test]
+    fn reph_goes_before_post_base_matra() {
+        // र्का: the reph sits between ka and the aa matra
+        let mut buffer = reordered("\u{0930}\u{094D}\u{0915}\u{093E}");
+        form_reph(&mut buffer);
+        final_reordering(&mut buffer, Script::Devanagari, HALANT);
+        assert_eq!(glyph_ids(&buffer), [102, 1, 103]);
+    }
+
+    #[test]
+    fn pre_base_matra_moves_after_standalone_halant() {
+        // क्षि without a half form for ka: the matra goes next to ssa
+        let mut buffer = reordered("\u{0915}\u{094D}\u{0937}\u{093F}");
+        assert_eq!(glyph_ids(&buffer), [103, 100, HALANT, 102]);
+
+        final_reordering(&mut buffer, Script::Devanagari, HALANT);
+        assert_eq!(glyph_ids(&buffer), [100, HALANT, 103, 102]);
+    }
+
+    #[test]
+    fn pre_base_matra_moves_after_zwnj() {
+        // क्‌षि: ZWNJ keeps the halant explicit, and the matra follows it
+        let mut buffer = reordered("\u{0915}\u{094D}\u{200C}\u{0937}\u{093F}");
+        final_reordering(&mut buffer, Script::Devanagari, HALANT);
+        assert_eq!(glyph_ids(&buffer), [100, HALANT, 102, 104, 103]);
+    }
+
+    #[test]
+    fn pre_base_matra_stays_before_half_form() {
+        // With a half form the halant is gone and the matra stays in front
+        let mut buffer = reordered("\u{0915}\u{094D}\u{0937}\u{093F}");
+        buffer.glyphs.remove(2);
+        final_reordering(&mut buffer, Script::Devanagari, HALANT);
+        assert_eq!(glyph_ids(&buffer), [103, 100, 102]);
+    }
+}
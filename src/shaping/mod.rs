@@ -0,0 +1,351 @@
+mod arabic;
+pub mod buffer;
+mod hangul;
+mod indic;
+
+pub use buffer::{Direction, GlyphBuffer, GlyphInfo, GlyphPosition, ScaledPosition};
+
+use crate::font::TrueTypeFont;
+use core::ops::Range;
+
+use crate::bidi::{mirrored_char, BidiInfo};
+use crate::script::{resolve_scripts, Script};
+use crate::tables::cmap::is_variation_selector;
+use crate::shaping::buffer::{GLOBAL_MASK, RTLM_MASK};
+use crate::Vec;
+
+/// A four byte OpenType tag such as `*b"liga"` or `*b"latn"`.
+pub type Tag = [u8; 4];
+
+/// The default script tag.
+pub const DEFAULT_SCRIPT: Tag = *b"DFLT";
+
+/// The default language system tag.
+pub const DEFAULT_LANGUAGE: Tag = *b"dflt";
+
+/// GSUB features applied unless turned off in [`ShapingOptions::features`].
+const DEFAULT_GSUB_FEATURES: [Tag; 7] = [*b"ccmp", *b"locl", *b"rlig", *b"rclt", *b"calt", *b"liga", *b"clig"];
+
+/// GPOS features applied unless turned off in [`ShapingOptions::features`].
+const DEFAULT_GPOS_FEATURES: [Tag; 6] = [*b"kern", *b"mark", *b"mkmk", *b"curs", *b"abvm", *b"blwm"];
+
+/// An OpenType feature request.
+///
+/// A value of `0` disables the feature, `1` enables it and, for alternate
+/// substitutions, higher values pick the n-th alternate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Feature {
+    pub tag: Tag,
+    pub value: u32,
+}
+
+impl Feature {
+    pub const fn new(tag: Tag, value: u32) -> Self {
+        Feature { tag, value }
+    }
+
+    pub const fn on(tag: Tag) -> Self {
+        Feature { tag, value: 1 }
+    }
+
+    pub const fn off(tag: Tag) -> Self {
+        Feature { tag, value: 0 }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShapingOptions {
+    pub script: Tag,
+    pub language: Tag,
+    pub direction: Direction,
+    /// Features to enable on top of the defaults, or to disable with a value of `0`.
+    pub features: Vec<Feature>,
+}
+
+impl Default for ShapingOptions {
+    fn default() -> Self {
+        ShapingOptions {
+            script: DEFAULT_SCRIPT,
+            language: DEFAULT_LANGUAGE,
+            direction: Direction::LeftToRight,
+            features: Vec::new(),
+        }
+    }
+}
+
+/// A positioned glyph. Advances and offsets are in font units; multiply by
+/// [`TrueTypeFont::scale_for_size`] to get pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ShapedGlyph {
+    pub glyph_id: u32,
+    /// Byte offset in the source text of the first character of this glyph.
+    pub cluster: usize,
+    pub x_advance: i32,
+    pub y_advance: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+}
+
+impl ShapingOptions {
+    /// The default features merged with the requested ones, each paired with
+    /// the glyph mask it applies to.
+    pub(crate) fn feature_list(&self, defaults: &[Tag]) -> Vec<(Feature, u32)> {
+        let mut features: Vec<(Feature, u32)> = defaults.iter().map(|&tag| (Feature::on(tag), GLOBAL_MASK)).collect();
+
+        for &requested in &self.features {
+            match features.iter_mut().find(|(f, _)| f.tag == requested.tag) {
+                Some((f, _)) => f.value = requested.value,
+                None => features.push((requested, GLOBAL_MASK)),
+            }
+        }
+
+        features
+    }
+
+    pub(crate) fn is_enabled(&self, tag: Tag) -> bool {
+        !self.features.iter().any(|f| f.tag == tag && f.value == 0)
+    }
+}
+
+impl TrueTypeFont {
+    /// Maps `text` through the cmap and runs it through GSUB and GPOS,
+    /// using the legacy `kern` table when GPOS has no kerning.
+    ///
+    /// Glyphs are returned in visual order, so right-to-left runs come back
+    /// reversed.
+    pub fn shape(&self, text: &str, options: &ShapingOptions) -> Vec<ShapedGlyph> {
+        match &options.script {
+            b"arab" => return self.shape_arabic(text, options),
+            b"deva" | b"dev2" | b"beng" | b"bng2" | b"taml" | b"tml2" => return self.shape_indic(text, options),
+            b"hang" => return self.shape_hangul(text, options),
+            _ => {}
+        }
+
+        let mut buffer = self.glyph_buffer(text);
+        buffer.direction = options.direction;
+        self.mirror_glyphs(&mut buffer, text);
+
+        self.substitute_default(&mut buffer, options, &[]);
+        self.position_default(&mut buffer, options, &[]);
+        self.hide_default_ignorables(&mut buffer, text);
+
+        shaped_glyphs(buffer)
+    }
+
+    /// Shapes mixed-script, mixed-direction text. The text is split into
+    /// bidi level runs and script runs, and each is shaped in its own
+    /// direction with the OpenType tag of its script, unless `options.script`
+    /// names one. Glyphs come back in visual order, with clusters as byte
+    /// offsets into `text`.
+    pub fn shape_text(&self, text: &str, options: &ShapingOptions) -> Vec<ShapedGlyph> {
+        let info = BidiInfo::new(text, None);
+        let scripts = resolve_scripts(text);
+
+        info.paragraphs.iter()
+            .flat_map(|paragraph| self.shape_line(text, &info, &scripts, paragraph.range.clone(), options))
+            .collect()
+    }
+
+    /// Shapes one line of `text`, which must lie within a single bidi
+    /// paragraph, into glyphs in visual order. `scripts` are the resolved
+    /// scripts of every byte of `text`.
+    pub(crate) fn shape_line(
+        &self,
+        text: &str,
+        info: &BidiInfo,
+        scripts: &[Script],
+        line: Range<usize>,
+        options: &ShapingOptions,
+    ) -> Vec<ShapedGlyph> {
+        let mut glyphs = Vec::new();
+
+        for run in info.visual_runs(line) {
+            let mut pieces: Vec<Range<usize>> = Vec::new();
+            for (offset, c) in text[run.range.clone()].char_indices() {
+                let offset = run.range.start + offset;
+                match pieces.last_mut() {
+                    Some(piece) if scripts[piece.start] == scripts[offset] => piece.end = offset + c.len_utf8(),
+                    _ => pieces.push(offset..offset + c.len_utf8()),
+                }
+            }
+
+            if run.direction().is_rtl() {
+                pieces.reverse();
+            }
+
+            for piece in pieces {
+                let script = if options.script == DEFAULT_SCRIPT { scripts[piece.start].opentype_tag() } else { options.script };
+                let direction = if options.direction.is_vertical() { options.direction } else { run.direction() };
+                let piece_options = ShapingOptions { script, direction, ..options.clone() };
+
+                glyphs.extend(self.shape(&text[piece.clone()], &piece_options).into_iter().map(|mut glyph| {
+                    glyph.cluster += piece.start;
+                    glyph
+                }));
+            }
+        }
+
+        glyphs
+    }
+
+    /// A buffer with one glyph per character, clustered by byte offset.
+    pub(crate) fn glyph_buffer(&self, text: &str) -> GlyphBuffer {
+        let mut buffer = GlyphBuffer::new();
+        for (cluster, c) in text.char_indices() {
+            buffer.push(self.get_glyph_id(c), cluster);
+        }
+        self.apply_variation_selectors(&mut buffer, text);
+        buffer
+    }
+
+    /// Swaps each base character followed by a variation selector for the
+    /// glyph the cmap gives that sequence. The selectors keep their own
+    /// glyphs until [`TrueTypeFont::hide_default_ignorables`] hides them.
+    pub(crate) fn apply_variation_selectors(&self, buffer: &mut GlyphBuffer, text: &str) {
+        for info in &mut buffer.glyphs {
+            let mut chars = text.get(info.cluster..).into_iter().flat_map(str::chars);
+            let (Some(c), Some(selector)) = (chars.next(), chars.next()) else { continue };
+
+            if is_variation_selector(selector)
+                && info.glyph_id == self.get_glyph_id(c)
+                && let Some(id) = self.glyph_for_variation(c, selector)
+            {
+                info.glyph_id = id;
+            }
+        }
+    }
+
+    /// Swaps characters such as brackets for their mirrored forms in
+    /// right-to-left text, marking those the cmap cannot mirror for `rtlm`.
+    pub(crate) fn mirror_glyphs(&self, buffer: &mut GlyphBuffer, text: &str) {
+        if !buffer.direction.is_rtl() {
+            return;
+        }
+
+        for info in &mut buffer.glyphs {
+            let Some(c) = text.get(info.cluster..).and_then(|rest| rest.chars().next()) else { continue };
+
+            match mirrored_char(c).map(|m| self.get_glyph_id(m)) {
+                Some(id) if id != 0 => info.glyph_id = id,
+                _ => info.mask |= RTLM_MASK,
+            }
+        }
+    }
+
+    /// Runs the default GSUB features plus any script specific `extra` ones.
+    /// Vertical text also gets its vertical alternates, from `vrt2` when the
+    /// font has it and `vert` otherwise.
+    pub(crate) fn substitute_default(&self, buffer: &mut GlyphBuffer, options: &ShapingOptions, extra: &[(Feature, u32)]) {
+        let mut defaults = DEFAULT_GSUB_FEATURES.to_vec();
+        if buffer.direction.is_vertical() {
+            let vrt2 = self.gsub.has_feature(options.script, options.language, *b"vrt2");
+            defaults.push(if vrt2 { *b"vrt2" } else { *b"vert" });
+        }
+
+        let mut features = options.feature_list(&defaults);
+        features.extend_from_slice(extra);
+
+        if buffer.direction.is_rtl() && options.is_enabled(*b"rtlm") {
+            features.push((Feature::on(*b"rtlm"), RTLM_MASK));
+        }
+
+        let plan = self.gsub.plan(options.script, options.language, &features);
+        self.apply_gsub_plan(buffer, &plan);
+    }
+
+    /// Runs the default GPOS features plus any `extra` ones, falling back
+    /// to the `kern` table when GPOS offers no kerning. Vertical text is
+    /// kerned with `vkrn` instead, and never from the `kern` table.
+    pub(crate) fn position_default(&self, buffer: &mut GlyphBuffer, options: &ShapingOptions, extra: &[(Feature, u32)]) {
+        let vertical = buffer.direction.is_vertical();
+        let defaults = DEFAULT_GPOS_FEATURES.map(|tag| if vertical && tag == *b"kern" { *b"vkrn" } else { tag });
+
+        let mut features = options.feature_list(&defaults);
+        features.extend_from_slice(extra);
+
+        self.init_positions(buffer);
+
+        let plan = self.gpos.plan(options.script, options.language, &features);
+        self.apply_gpos_plan(buffer, &plan);
+
+        if !vertical && options.is_enabled(*b"kern") && !self.gpos.has_feature(options.script, options.language, *b"kern") {
+            self.apply_legacy_kerning(buffer);
+        }
+
+        self.finish_positions(buffer);
+    }
+
+    /// Positions glyphs by their advances and `kern` alone, through GPOS or
+    /// else the legacy `kern` table, for measuring text without shaping it.
+    pub(crate) fn position_kerning(&self, buffer: &mut GlyphBuffer, options: &ShapingOptions) {
+        self.init_positions(buffer);
+
+        if !options.is_enabled(*b"kern") {
+            return;
+        }
+
+        if self.gpos.has_feature(options.script, options.language, *b"kern") {
+            let plan = self.gpos.plan(options.script, options.language, &[(Feature::on(*b"kern"), GLOBAL_MASK)]);
+            self.apply_gpos_plan(buffer, &plan);
+        } else {
+            self.apply_legacy_kerning(buffer);
+        }
+    }
+
+    /// Replaces format controls such as ZWJ and ZWNJ, which only steer
+    /// shaping, with a zero width space glyph so they never render.
+    pub(crate) fn hide_default_ignorables(&self, buffer: &mut GlyphBuffer, text: &str) {
+        let space = self.get_glyph_id(' ');
+
+        for (info, pos) in buffer.glyphs.iter_mut().zip(buffer.positions.iter_mut()) {
+            let Some(c) = text.get(info.cluster..).and_then(|rest| rest.chars().next()) else { continue };
+            if is_default_ignorable(c) {
+                info.glyph_id = space;
+                pos.x_advance = 0;
+                pos.y_advance = 0;
+            }
+        }
+    }
+
+    /// `kern` pairs are in visual order, so a right-to-left run looks each
+    /// pair up the other way round and adjusts the glyph on the left.
+    fn apply_legacy_kerning(&self, buffer: &mut GlyphBuffer) {
+        let rtl = buffer.direction.is_rtl();
+
+        for i in 1..buffer.glyphs.len() {
+            let (left, right) = if rtl { (i, i - 1) } else { (i - 1, i) };
+            let left_glyph = buffer.glyphs[left].glyph_id;
+            let right_glyph = buffer.glyphs[right].glyph_id;
+
+            if let Some((along, cross)) = self.kern.adjustment(left_glyph as u16, right_glyph as u16) {
+                buffer.positions[left].x_advance += along as i32;
+                buffer.positions[right].y_offset += cross as i32;
+            }
+        }
+    }
+}
+
+pub(crate) fn is_default_ignorable(c: char) -> bool {
+    matches!(c as u32, 0x00AD | 0x034F | 0x180B..=0x180F | 0x200B..=0x200F | 0x202A..=0x202E
+        | 0x2060..=0x2064 | 0x3164 | 0xFE00..=0xFE0F | 0xFEFF | 0xFFA0 | 0xE0100..=0xE01EF)
+}
+
+/// Flattens a positioned buffer into visual order.
+pub(crate) fn shaped_glyphs(buffer: GlyphBuffer) -> Vec<ShapedGlyph> {
+    let mut glyphs: Vec<ShapedGlyph> = buffer.glyphs.iter().zip(buffer.positions.iter())
+        .map(|(info, pos)| ShapedGlyph {
+            glyph_id: info.glyph_id,
+            cluster: info.cluster,
+            x_advance: pos.x_advance,
+            y_advance: pos.y_advance,
+            x_offset: pos.x_offset,
+            y_offset: pos.y_offset,
+        })
+        .collect();
+
+    if buffer.direction.is_rtl() {
+        glyphs.reverse();
+    }
+
+    glyphs
+}
@@ -10,15 +10,22 @@ use crate::font::{
     TrueTypeFont
 };
 
+use core::ops::RangeInclusive;
+
+use crate::tables::name::{mac_roman_byte, mac_roman_char};
 use crate::Vec;
 use crate::vec;
 
 pub struct CmapTable {
     pub offset: usize,
+    /// End of the table in the font data.
+    pub end: usize,
     pub header: CmapHeader,
     pub encodings: Vec<EncodingRecord>,
     pub encoding_formats: Vec<u16>,
     pub subtables: Vec<SupportedCmapFormats>,
+    /// Index in `subtables` of the subtable used for character lookups.
+    pub selected: usize,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -46,6 +53,11 @@ pub enum SupportedCmapFormats {
         encoding_id: u16,
         data: CmapFormat0,
     },
+    Format2 {
+        platform_id: u16,
+        encoding_id: u16,
+        data: CmapFormat2,
+    },
     Format4 {
         platform_id: u16,
         encoding_id: u16,
@@ -56,11 +68,31 @@ pub enum SupportedCmapFormats {
         encoding_id: u16,
         data: CmapFormat6,
     },
+    Format8 {
+        platform_id: u16,
+        encoding_id: u16,
+        data: CmapFormat8,
+    },
+    Format10 {
+        platform_id: u16,
+        encoding_id: u16,
+        data: CmapFormat10,
+    },
     Format12 {
         platform_id: u16,
         encoding_id: u16,
         data: CmapFormat12,
     },
+    Format13 {
+        platform_id: u16,
+        encoding_id: u16,
+        data: CmapFormat13,
+    },
+    Format14 {
+        platform_id: u16,
+        encoding_id: u16,
+        data: CmapFormat14,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -71,6 +103,27 @@ pub struct CmapFormat0 {
     pub glyph_id_array: [u8; 256],
 }
 
+/// High-byte mapping for mixed one and two byte encodings such as
+/// Shift-JIS and Big5.
+#[derive(Clone, Debug)]
+pub struct CmapFormat2 {
+    /// Index into `sub_headers` for each high byte; 0 marks a single byte
+    /// character.
+    pub sub_header_keys: Vec<u16>,
+    pub sub_headers: Vec<SubHeader>,
+    pub glyph_id_array: Vec<u16>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SubHeader {
+    pub first_code: u16,
+    pub entry_count: u16,
+    pub id_delta: i16,
+    /// Index in `glyph_id_array` of the glyph for `first_code`, resolved
+    /// from the subheader's `idRangeOffset`.
+    pub first_glyph_index: usize,
+}
+
 #[derive(Clone, Debug)]
 pub struct CmapFormat4 {
     pub _format: u16,
@@ -98,6 +151,21 @@ pub struct CmapFormat6 {
     pub glyph_id_array: Vec<u16>,
 }
 
+/// Mixed 16 and 32 bit character codes in sequential groups.
+#[derive(Debug, Clone)]
+pub struct CmapFormat8 {
+    /// Bit per 16 bit value telling whether it starts a 32 bit code.
+    pub _is32: Vec<u8>,
+    pub groups: Vec<SequentialMapGroup>,
+}
+
+/// A trimmed array of glyphs for one range of 32 bit character codes.
+#[derive(Debug, Clone)]
+pub struct CmapFormat10 {
+    pub start_char_code: u32,
+    pub glyph_id_array: Vec<u16>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CmapFormat12 {
     pub _format: u16,
@@ -115,23 +183,324 @@ pub struct SequentialMapGroup {
     pub start_glyph_id: u32,
 }
 
+/// Many-to-one ranges, mapping every character of a group to one glyph.
+/// Used by last resort fonts.
+#[derive(Debug, Clone)]
+pub struct CmapFormat13 {
+    pub groups: Vec<ConstantMapGroup>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConstantMapGroup {
+    pub start_char_code: u32,
+    pub end_char_code: u32,
+    pub glyph_id: u32,
+}
+
+/// Unicode Variation Sequences: glyphs for a base character followed by a
+/// variation selector.
+#[derive(Debug, Clone)]
+pub struct CmapFormat14 {
+    /// Sorted by selector.
+    pub var_selector_records: Vec<VariationSelectorRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariationSelectorRecord {
+    pub var_selector: u32,
+    /// Sorted ranges of base characters that keep their default glyph.
+    pub default_uvs: Vec<UnicodeRange>,
+    /// Sorted base characters with a glyph of their own for this selector.
+    pub non_default_uvs: Vec<UvsMapping>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnicodeRange {
+    pub start_unicode_value: u32,
+    pub additional_count: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct UvsMapping {
+    pub unicode_value: u32,
+    pub glyph_id: u16,
+}
+
+/// What a format 14 subtable says about a variation sequence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Variation {
+    Default,
+    Glyph(u32),
+}
+
+impl CmapFormat14 {
+    fn variation(&self, base: u32, selector: u32) -> Option<Variation> {
+        let i = self.var_selector_records.binary_search_by_key(&selector, |r| r.var_selector).ok()?;
+        let record = &self.var_selector_records[i];
+
+        if let Ok(j) = record.non_default_uvs.binary_search_by_key(&base, |m| m.unicode_value) {
+            return Some(Variation::Glyph(record.non_default_uvs[j].glyph_id as u32));
+        }
+
+        let j = match record.default_uvs.binary_search_by_key(&base, |r| r.start_unicode_value) {
+            Ok(j) => j,
+            Err(j) => j.checked_sub(1)?,
+        };
+        let range = &record.default_uvs[j];
+        (base <= range.start_unicode_value + range.additional_count as u32).then_some(Variation::Default)
+    }
+}
+
+/// The group whose inclusive `(start, end)` range holds `codepoint`, in
+/// groups sorted by range.
+fn find_group<T>(groups: &[T], codepoint: u32, range: impl Fn(&T) -> (u32, u32)) -> Option<&T> {
+    // The first group ending at or after the codepoint
+    match groups.binary_search_by_key(&codepoint, |group| range(group).1) {
+        Ok(i) | Err(i) if i < groups.len() && codepoint >= range(&groups[i]).0 => Some(&groups[i]),
+        _ => None,
+    }
+}
+
+/// Legacy double byte encodings, whose codes are not Unicode.
+fn is_double_byte_encoding(encoding: (u16, u16)) -> bool {
+    matches!(encoding, (3, 2..=6) | (1, 1..=3) | (1, 25))
+}
+
+#[inline]
+fn get_u24_be(base: &[u8], offset: usize) -> u32 {
+    (base[offset] as u32) << 16 | (base[offset + 1] as u32) << 8 | base[offset + 2] as u32
+}
+
+/// Whether `c` is a variation selector (VS1 to VS256).
+pub(crate) fn is_variation_selector(c: char) -> bool {
+    matches!(c as u32, 0xFE00..=0xFE0F | 0xE0100..=0xE01EF)
+}
+
 impl CmapTable {
     pub fn new() -> CmapTable {
         CmapTable {
             offset: 0,
+            end: 0,
             header: CmapHeader { _version: 0, num_tables: 0 },
             encodings: Vec::new(),
             encoding_formats: Vec::new(),
             subtables: Vec::new(),
+            selected: 0,
         }
     }
 }
 
+impl SupportedCmapFormats {
+    /// Platform and encoding ids of the subtable.
+    pub fn encoding(&self) -> (u16, u16) {
+        match self {
+            SupportedCmapFormats::Format0 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format2 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format4 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format6 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format8 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format10 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format12 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format13 { platform_id, encoding_id, .. } |
+            SupportedCmapFormats::Format14 { platform_id, encoding_id, .. } => (*platform_id, *encoding_id),
+        }
+    }
+
+    /// Whether the subtable maps single characters; format 14 only maps
+    /// variation sequences.
+    pub fn maps_characters(&self) -> bool {
+        !matches!(self, SupportedCmapFormats::Format14 { .. })
+    }
+
+    /// Glyph for a character code in the subtable's own encoding.
+    pub fn glyph_id(&self, codepoint: u32) -> u32 {
+        match self {
+            SupportedCmapFormats::Format0 { data, .. } => {
+                if codepoint < 256 {
+                    data.glyph_id_array[codepoint as usize] as u32
+                } else {
+                    0
+                }
+            }
+
+            SupportedCmapFormats::Format2 { data, .. } => {
+                if codepoint > 0xFFFF {
+                    return 0;
+                }
+
+                let (high, low) = (codepoint >> 8, codepoint & 0xFF);
+                let key = if high == 0 {
+                    // A lead byte on its own is not a character
+                    if data.sub_header_keys[low as usize] != 0 {
+                        return 0;
+                    }
+                    0
+                } else {
+                    match data.sub_header_keys[high as usize] {
+                        0 => return 0,
+                        key => key,
+                    }
+                };
+
+                let Some(header) = data.sub_headers.get(key as usize) else { return 0 };
+                let first_code = header.first_code as u32;
+                if low < first_code || low >= first_code + header.entry_count as u32 {
+                    return 0;
+                }
+
+                // An idRangeOffset pointing before the glyph array maps nothing
+                let index = header.first_glyph_index.checked_add((low - first_code) as usize);
+                match index.and_then(|index| data.glyph_id_array.get(index)) {
+                    Some(&gid) if gid != 0 => ((gid as i32 + header.id_delta as i32) as u32) & 0xFFFF,
+                    _ => 0,
+                }
+            }
+
+            SupportedCmapFormats::Format4 { data, .. } => {
+                if codepoint > 0xFFFF {
+                    return 0;
+                }
+
+                match data.end_count.binary_search(&(codepoint as u16)) {
+                    Ok(i) | Err(i) if i < data.end_count.len() && codepoint as u16 >= data.start_count[i] => {
+                        if data.id_range_offset[i] == 0 {
+
+                            ((codepoint as i32 + data.id_delta[i] as i32) as u32) & 0xFFFF
+                        } else {
+                            let seg_count = data.seg_count_x2 / 2;
+                            let index = (data.id_range_offset[i] / 2 +
+                                (codepoint as u16 - data.start_count[i]) -
+                                (seg_count - i as u16)) as usize;
+                            if index < data.glyph_id_array.len() {
+                                let gid = data.glyph_id_array[index];
+                                if gid != 0 {
+                                    ((gid as i32 + data.id_delta[i] as i32) as u32) & 0xFFFF
+                                } else {
+                                    0
+                                }
+                            } else {
+                                0
+                            }
+                        }
+                    }
+                    _ => 0
+                }
+            }
+
+            SupportedCmapFormats::Format6 { data, .. } => {
+                if codepoint > 0xFFFF {
+                    return 0;
+                }
+
+                let index = codepoint as u16;
+                if index >= data.first_code && (index - data.first_code) < data.entry_count {
+                    let array_index = (index - data.first_code) as usize;
+                    if array_index < data.glyph_id_array.len() {
+                        data.glyph_id_array[array_index] as u32
+                    } else {
+                        0
+                    }
+                } else {
+                    0
+                }
+            }
+
+            SupportedCmapFormats::Format8 { data, .. } => {
+                find_group(&data.groups, codepoint, |g| (g.start_char_code, g.end_char_code))
+                    .map_or(0, |group| group.start_glyph_id.wrapping_add(codepoint - group.start_char_code))
+            }
+
+            SupportedCmapFormats::Format10 { data, .. } => {
+                codepoint.checked_sub(data.start_char_code)
+                    .and_then(|index| data.glyph_id_array.get(index as usize))
+                    .map_or(0, |&glyph_id| glyph_id as u32)
+            }
+
+            SupportedCmapFormats::Format12 { data, .. } => {
+                if data.num_groups as usize != data.groups.len() {
+                    0
+                } else {
+                    find_group(&data.groups, codepoint, |g| (g.start_char_code, g.end_char_code))
+                        .map_or(0, |group| group.start_glyph_id.wrapping_add(codepoint - group.start_char_code))
+                }
+            }
+
+            SupportedCmapFormats::Format13 { data, .. } => {
+                find_group(&data.groups, codepoint, |g| (g.start_char_code, g.end_char_code))
+                    .map_or(0, |group| group.glyph_id)
+            }
+
+            SupportedCmapFormats::Format14 { .. } => 0,
+        }
+    }
+
+    /// Ranges of character codes the subtable may map, in the subtable's own
+    /// encoding. Codes inside them can still map to glyph 0.
+    fn code_ranges(&self) -> Vec<RangeInclusive<u32>> {
+        match self {
+            SupportedCmapFormats::Format0 { .. } => vec![0..=0xFF],
+
+            SupportedCmapFormats::Format2 { data, .. } => {
+                let mut ranges = Vec::new();
+                for (high, &key) in data.sub_header_keys.iter().enumerate() {
+                    let high = high as u32;
+                    let Some(header) = data.sub_headers.get(key as usize) else { continue };
+
+                    if key == 0 {
+                        ranges.push(high..=high);
+                    } else if header.entry_count > 0 {
+                        let first = header.first_code as u32;
+                        let last = (first + header.entry_count as u32 - 1).min(0xFF);
+                        ranges.push(high << 8 | first..=high << 8 | last);
+                    }
+                }
+                ranges
+            }
+
+            // The final 0xFFFF segment only terminates the table
+            SupportedCmapFormats::Format4 { data, .. } => data.start_count.iter().zip(&data.end_count)
+                .map(|(&start, &end)| start as u32..=end.min(0xFFFE) as u32)
+                .collect(),
+
+            SupportedCmapFormats::Format6 { data, .. } if data.entry_count > 0 => {
+                vec![data.first_code as u32..=data.first_code as u32 + data.entry_count as u32 - 1]
+            }
+
+            SupportedCmapFormats::Format8 { data: CmapFormat8 { groups, .. }, .. } |
+            SupportedCmapFormats::Format12 { data: CmapFormat12 { groups, .. }, .. } => groups.iter()
+                .map(|group| group.start_char_code..=group.end_char_code.min(0x10FFFF))
+                .collect(),
+
+            SupportedCmapFormats::Format10 { data, .. } if !data.glyph_id_array.is_empty() => {
+                let end = data.start_char_code.saturating_add(data.glyph_id_array.len() as u32 - 1);
+                vec![data.start_char_code..=end.min(0x10FFFF)]
+            }
+
+            SupportedCmapFormats::Format13 { data, .. } => data.groups.iter()
+                .map(|group| group.start_char_code..=group.end_char_code.min(0x10FFFF))
+                .collect(),
+
+            _ => Vec::new(),
+        }
+    }
+
+    /// Every character code the subtable maps to a glyph other than 0, with
+    /// its glyph, in the subtable's own encoding. Codes are looked up as the
+    /// iterator advances.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.code_ranges().into_iter().flatten().filter_map(|code| match self.glyph_id(code) {
+            0 => None,
+            glyph_id => Some((code, glyph_id)),
+        })
+    }
+}
+
 impl TrueTypeFont {
     pub fn load_cmap(&mut self, font_bytes: &[u8]) {
         for table in &self.tables {
             if table.table_tag == "cmap".as_bytes() {
                 self.cmap.offset = table.offset as usize;
+                self.cmap.end = (self.cmap.offset + table.length as usize).min(font_bytes.len());
                 self.cmap.header = CmapHeader {
                     _version: get_u16_be(font_bytes, self.cmap.offset),
                     num_tables: get_u16_be(font_bytes, self.cmap.offset + 2),
@@ -170,6 +539,8 @@ impl TrueTypeFont {
 
     pub fn load_cmap_subtables(&mut self, font_bytes: &[u8]) {
         let mut count = 0;
+        // Group counts are limited to what the rest of the table can hold
+        let fit = |start: usize, size: usize| self.cmap.end.saturating_sub(start) / size;
         for sf in &self.cmap.encoding_formats {
             let platform_id = self.cmap.encodings[count].platform_id;
             let encoding_id = self.cmap.encodings[count].encoding_id;
@@ -190,6 +561,47 @@ impl TrueTypeFont {
                         data: fmt,
                     });
                 }
+                2 => {
+                    let length = get_u16_be(font_bytes, offset + 2) as usize;
+                    let sub_header_keys: Vec<u16> = (0..256)
+                        .map(|i| get_u16_be(font_bytes, offset + 6 + i * 2) / 8)
+                        .collect();
+
+                    let headers_offset = offset + 518;
+                    let num_headers = sub_header_keys.iter().max().map_or(0, |&key| key as usize + 1);
+                    let glyphs_offset = headers_offset + num_headers * 8;
+
+                    let mut fmt = CmapFormat2 {
+                        sub_header_keys,
+                        sub_headers: Vec::with_capacity(num_headers),
+                        glyph_id_array: Vec::new(),
+                    };
+
+                    for i in 0..num_headers {
+                        let header = headers_offset + i * 8;
+                        // idRangeOffset counts from its own position
+                        let first_glyph = header + 6 + get_u16_be(font_bytes, header + 6) as usize;
+
+                        fmt.sub_headers.push(SubHeader {
+                            first_code: get_u16_be(font_bytes, header),
+                            entry_count: get_u16_be(font_bytes, header + 2),
+                            id_delta: get_u16_be(font_bytes, header + 4) as i16,
+                            first_glyph_index: first_glyph.checked_sub(glyphs_offset).map_or(usize::MAX, |bytes| bytes / 2),
+                        });
+                    }
+
+                    let mut base_offset = glyphs_offset;
+                    while base_offset + 2 <= offset + length {
+                        fmt.glyph_id_array.push(get_u16_be(font_bytes, base_offset));
+                        base_offset += 2;
+                    }
+
+                    self.cmap.subtables.push(SupportedCmapFormats::Format2 {
+                        platform_id,
+                        encoding_id,
+                        data: fmt,
+                    });
+                }
                 4 => {
                     let seg_count = get_u16_be(font_bytes, offset + 6) as usize / 2;
                     let mut fmt = CmapFormat4 {
@@ -267,18 +679,63 @@ impl TrueTypeFont {
                         data: fmt,
                     });
                 }
+                8 => {
+                    let num_groups = (get_u32_be(font_bytes, offset + 8204) as usize).min(fit(offset + 8208, size_of::<SequentialMapGroup>()));
+                    let mut fmt = CmapFormat8 {
+                        _is32: font_bytes[offset + 12..offset + 8204].to_vec(),
+                        groups: Vec::with_capacity(num_groups),
+                    };
+
+                    let mut base_offset = offset + 8208;
+                    for _ in 0..num_groups {
+                        fmt.groups.push(SequentialMapGroup {
+                            start_char_code: get_u32_be(font_bytes, base_offset),
+                            end_char_code: get_u32_be(font_bytes, base_offset + 4),
+                            start_glyph_id: get_u32_be(font_bytes, base_offset + 8),
+                        });
+                        base_offset += size_of::<SequentialMapGroup>();
+                    }
+
+                    self.cmap.subtables.push(SupportedCmapFormats::Format8 {
+                        platform_id,
+                        encoding_id,
+                        data: fmt,
+                    });
+                }
+                10 => {
+                    let num_chars = (get_u32_be(font_bytes, offset + 16) as usize).min(fit(offset + 20, 2));
+                    let mut fmt = CmapFormat10 {
+                        start_char_code: get_u32_be(font_bytes, offset + 12),
+                        glyph_id_array: Vec::with_capacity(num_chars),
+                    };
+
+                    let mut base_offset = offset + 20;
+                    for _ in 0..num_chars {
+                        fmt.glyph_id_array.push(get_u16_be(font_bytes, base_offset));
+                        base_offset += 2;
+                    }
+
+                    self.cmap.subtables.push(SupportedCmapFormats::Format10 {
+                        platform_id,
+                        encoding_id,
+                        data: fmt,
+                    });
+                }
                 12 => {
+                    // A truncated table reads fewer groups than it declares
+                    // and then maps nothing
+                    let num_groups = (get_u32_be(font_bytes, offset + 12) as usize).min(fit(offset + 16, size_of::<SequentialMapGroup>()));
                     let mut fmt = CmapFormat12 {
                         _format: get_u16_be(font_bytes, offset),
                         _reserved: get_u16_be(font_bytes, offset + 2),
                         _length: get_u32_be(font_bytes, offset + 4),
                         _language: get_u32_be(font_bytes, offset + 8),
                         num_groups: get_u32_be(font_bytes, offset + 12),
-                        groups: Vec::with_capacity(get_u32_be(font_bytes, offset + 12) as usize),
+                        groups: Vec::with_capacity(num_groups),
                     };
 
                     let mut base_offset = offset + 16;
-                    for _ in 0..fmt.num_groups as usize {
+                    for _ in 0..num_groups {
                         let smg = SequentialMapGroup {
                             start_char_code: get_u32_be(font_bytes, base_offset),
                             end_char_code: get_u32_be(font_bytes, base_offset + 4),
@@ -295,108 +752,221 @@ impl TrueTypeFont {
                         data: fmt,
                     });
                 }
-                _ => {}
-            }
-            count += 1;
-        }
-
-        self.cmap.subtables.sort_by_key(|subtable| match subtable {
-            SupportedCmapFormats::Format0 { platform_id, encoding_id, .. } |
-            SupportedCmapFormats::Format4 { platform_id, encoding_id, .. } |
-            SupportedCmapFormats::Format6 { platform_id, encoding_id, .. } |
-            SupportedCmapFormats::Format12 { platform_id, encoding_id, .. } => {
-                match (*platform_id, *encoding_id) {
-                    (0, _) => 0,      // Unicode
-                    (3, 10) => 1,     // Windows
-                    (3, 1) => 2,      // Windows Unicode
-                    _ => 3,
-                }
-            }
-        });
-
-        self.cmap.subtables.truncate(1);
-    }
-
-    pub fn get_glyph_id(&self, codepoint: char) -> u32 {
-        let codepoint = codepoint as u32;
+                13 => {
+                    let num_groups = (get_u32_be(font_bytes, offset + 12) as usize).min(fit(offset + 16, size_of::<ConstantMapGroup>()));
+                    let mut fmt = CmapFormat13 { groups: Vec::with_capacity(num_groups) };
 
-        match &self.cmap.subtables[0] {
-            SupportedCmapFormats::Format0 { data, .. } => {
-                if codepoint < 256 {
-                    data.glyph_id_array[codepoint as usize] as u32
-                } else {
-                    0
-                }
-            }
-
-            SupportedCmapFormats::Format4 { data, .. } => {
-                match data.end_count.binary_search(&(codepoint as u16)) {
-                    Ok(i) | Err(i) if i < data.end_count.len() && codepoint as u16 >= data.start_count[i] => {
-                        if data.id_range_offset[i] == 0 {
-
-                            ((codepoint as i32 + data.id_delta[i] as i32) as u32) & 0xFFFF
-                        } else {
-                            let seg_count = data.seg_count_x2 / 2;
-                            let index = (data.id_range_offset[i] / 2 +
-                                (codepoint as u16 - data.start_count[i]) -
-                                (seg_count - i as u16)) as usize;
-                            if index < data.glyph_id_array.len() {
-                                let gid = data.glyph_id_array[index];
-                                if gid != 0 {
-                                    ((gid as i32 + data.id_delta[i] as i32) as u32) & 0xFFFF
-                                } else {
-                                    0
-                                }
-                            } else {
-                                0
-                            }
-                        }
+                    let mut base_offset = offset + 16;
+                    for _ in 0..num_groups {
+                        fmt.groups.push(ConstantMapGroup {
+                            start_char_code: get_u32_be(font_bytes, base_offset),
+                            end_char_code: get_u32_be(font_bytes, base_offset + 4),
+                            glyph_id: get_u32_be(font_bytes, base_offset + 8),
+                        });
+                        base_offset += size_of::<ConstantMapGroup>();
                     }
-                    _ => 0
-                }
-            }
 
-            SupportedCmapFormats::Format6 { data, .. } => {
-                let index = codepoint as u16;
-                if index >= data.first_code && index < data.first_code + data.entry_count {
-                    let array_index = (index - data.first_code) as usize;
-                    if array_index < data.glyph_id_array.len() {
-                        data.glyph_id_array[array_index] as u32
+                    self.cmap.subtables.push(SupportedCmapFormats::Format13 {
+                        platform_id,
+                        encoding_id,
+                        data: fmt,
+                    });
+                }
+                14 => {
+                    let num_records = if offset + 10 <= self.cmap.end {
+                        (get_u32_be(font_bytes, offset + 6) as usize).min(fit(offset + 10, 11))
                     } else {
                         0
-                    }
-                } else {
-                    0
-                }
-            }
+                    };
+                    let mut fmt = CmapFormat14 { var_selector_records: Vec::with_capacity(num_records) };
 
-            SupportedCmapFormats::Format12 { data, .. } => {
-                if data.groups.is_empty() || data.num_groups as usize != data.groups.len() {
-                    0
-                } else {
-                    match data.groups.binary_search_by_key(&codepoint, |g| g.end_char_code) {
-                        Ok(i) => {
-                            let group = &data.groups[i];
-                            if codepoint >= group.start_char_code {
-                                let glyph_offset = (codepoint - group.start_char_code);
-                                group.start_glyph_id.wrapping_add(glyph_offset)
-                            } else {
-                                0
+                    // Default and non-default UVS tables outside the cmap are skipped
+                    let uvs_table = |table_offset: usize| {
+                        offset.checked_add(table_offset)
+                            .filter(|&base| table_offset != 0 && base + 4 <= self.cmap.end)
+                    };
+
+                    for i in 0..num_records {
+                        let record = offset + 10 + i * 11;
+                        let default_offset = get_u32_be(font_bytes, record + 3) as usize;
+                        let non_default_offset = get_u32_be(font_bytes, record + 7) as usize;
+
+                        let mut default_uvs = Vec::new();
+                        if let Some(base) = uvs_table(default_offset) {
+                            let num_ranges = (get_u32_be(font_bytes, base) as usize).min(fit(base + 4, 4));
+                            for j in 0..num_ranges {
+                                default_uvs.push(UnicodeRange {
+                                    start_unicode_value: get_u24_be(font_bytes, base + 4 + j * 4),
+                                    additional_count: font_bytes[base + 7 + j * 4],
+                                });
                             }
                         }
-                        Err(i) if i > 0 => {
-                            let group = &data.groups[i - 1];
-                            if codepoint >= group.start_char_code && codepoint <= group.end_char_code {
-                                let glyph_offset = (codepoint - group.start_char_code);
-                                group.start_glyph_id.wrapping_add(glyph_offset)
-                            } else {
-                                0
+
+                        let mut non_default_uvs = Vec::new();
+                        if let Some(base) = uvs_table(non_default_offset) {
+                            let num_mappings = (get_u32_be(font_bytes, base) as usize).min(fit(base + 4, 5));
+                            for j in 0..num_mappings {
+                                non_default_uvs.push(UvsMapping {
+                                    unicode_value: get_u24_be(font_bytes, base + 4 + j * 5),
+                                    glyph_id: get_u16_be(font_bytes, base + 7 + j * 5),
+                                });
                             }
                         }
-                        _ => 0
+
+                        fmt.var_selector_records.push(VariationSelectorRecord {
+                            var_selector: get_u24_be(font_bytes, record),
+                            default_uvs,
+                            non_default_uvs,
+                        });
                     }
+
+                    self.cmap.subtables.push(SupportedCmapFormats::Format14 {
+                        platform_id,
+                        encoding_id,
+                        data: fmt,
+                    });
                 }
+                _ => {}
             }
+            count += 1;
+        }
+
+        self.cmap.subtables.sort_by_key(|subtable| match subtable.encoding() {
+            _ if !subtable.maps_characters() => 8,
+            // Last resort ranges only when nothing else maps characters
+            _ if matches!(subtable, SupportedCmapFormats::Format13 { .. }) => 7,
+            (0, 4) | (0, 6) => 0,   // Unicode full repertoire
+            (3, 10) => 1,           // Windows full repertoire
+            (0, _) => 2,            // Unicode BMP
+            (3, 1) => 3,            // Windows BMP
+            (3, 0) => 4,            // Windows Symbol
+            (1, 0) => 5,            // Macintosh Roman
+            _ => 6,
+        });
+        self.cmap.selected = 0;
+    }
+
+    pub fn get_glyph_id(&self, codepoint: char) -> u32 {
+        let Some(subtable) = self.cmap.subtables.get(self.cmap.selected) else { return 0 };
+
+        match subtable.encoding() {
+            // Symbol fonts map their characters at U+F020 to U+F0FF
+            (3, 0) => match subtable.glyph_id(codepoint as u32) {
+                0 if (codepoint as u32) < 0x100 => subtable.glyph_id(0xF000 + codepoint as u32),
+                glyph_id => glyph_id,
+            },
+            (1, 0) => mac_roman_byte(codepoint).map_or(0, |byte| subtable.glyph_id(byte as u32)),
+            // Only ASCII is shared between Unicode and double byte encodings
+            encoding if is_double_byte_encoding(encoding) => {
+                if codepoint.is_ascii() { subtable.glyph_id(codepoint as u32) } else { 0 }
+            }
+            _ => subtable.glyph_id(codepoint as u32),
+        }
+    }
+
+    /// Glyph for a character code in the encoding of the selected subtable,
+    /// such as a Shift-JIS or Big5 code in a double byte encoded cmap.
+    pub fn glyph_for_code(&self, code: u32) -> u32 {
+        self.cmap.subtables.get(self.cmap.selected).map_or(0, |subtable| subtable.glyph_id(code))
+    }
+
+    /// The glyph for `base` followed by the variation selector `selector`,
+    /// from the format 14 subtable. `None` when the font does not support
+    /// the sequence, in which case the base glyph should be used.
+    pub fn glyph_for_variation(&self, base: char, selector: char) -> Option<u32> {
+        let variation = self.cmap.subtables.iter().find_map(|subtable| match subtable {
+            SupportedCmapFormats::Format14 { data, .. } => data.variation(base as u32, selector as u32),
+            _ => None,
+        })?;
+
+        match variation {
+            Variation::Default => Some(self.get_glyph_id(base)).filter(|&glyph_id| glyph_id != 0),
+            Variation::Glyph(glyph_id) => Some(glyph_id),
         }
     }
-}
\ No newline at end of file
+
+    /// Platform and encoding ids of every cmap subtable the font has, in
+    /// the order they are preferred for character lookups.
+    pub fn cmap_encodings(&self) -> Vec<(u16, u16)> {
+        self.cmap.subtables.iter()
+            .filter(|subtable| subtable.maps_characters())
+            .map(SupportedCmapFormats::encoding)
+            .collect()
+    }
+
+    /// The platform and encoding ids of the subtable used for lookups.
+    pub fn cmap_encoding(&self) -> Option<(u16, u16)> {
+        self.cmap.subtables.get(self.cmap.selected).map(SupportedCmapFormats::encoding)
+    }
+
+    /// Switches character lookups to the subtable with these platform and
+    /// encoding ids. Returns `false`, keeping the current subtable, when the
+    /// font has no such subtable.
+    pub fn select_cmap_encoding(&mut self, platform_id: u16, encoding_id: u16) -> bool {
+        let Some(index) = self.cmap.subtables.iter()
+            .position(|subtable| subtable.maps_characters() && subtable.encoding() == (platform_id, encoding_id))
+        else {
+            return false;
+        };
+
+        self.cmap.selected = index;
+        true
+    }
+
+    /// Characters of the selected subtable with their glyphs, translating
+    /// its character codes to Unicode.
+    fn char_mappings(&self) -> impl Iterator<Item = (char, u32)> + '_ {
+        let subtable = self.cmap.subtables.get(self.cmap.selected);
+        let encoding = subtable.map(SupportedCmapFormats::encoding);
+
+        let mapped = subtable.into_iter().flat_map(SupportedCmapFormats::mappings).filter_map(move |(code, glyph_id)| {
+            let c = match encoding {
+                Some((1, 0)) if code < 0x100 => Some(mac_roman_char(code as u8)),
+                Some(encoding) if is_double_byte_encoding(encoding) => char::from_u32(code).filter(char::is_ascii),
+                _ => char::from_u32(code),
+            };
+            c.map(|c| (c, glyph_id))
+        });
+
+        // Symbol fonts also take Latin-1 input for their U+F0xx characters
+        let symbol_aliases = subtable.filter(|_| encoding == Some((3, 0))).into_iter().flat_map(|subtable| {
+            (0..0x100u32).filter_map(move |code| match subtable.glyph_id(code) {
+                0 => match subtable.glyph_id(0xF000 + code) {
+                    0 => None,
+                    glyph_id => char::from_u32(code).map(|c| (c, glyph_id)),
+                },
+                _ => None,
+            })
+        });
+
+        // Both run in code order, so merging them keeps `codepoints` sorted
+        let mut mapped = mapped.peekable();
+        let mut symbol_aliases = symbol_aliases.peekable();
+        core::iter::from_fn(move || match (mapped.peek(), symbol_aliases.peek()) {
+            (Some(&(c, _)), Some(&(alias, _))) if alias < c => symbol_aliases.next(),
+            (Some(_), _) => mapped.next(),
+            (None, _) => symbol_aliases.next(),
+        })
+    }
+
+    /// Every character the selected cmap subtable maps to a glyph, in the
+    /// order of its character codes. The cmap is read as the iterator
+    /// advances, so this suits building fallback chains or coverage reports
+    /// without collecting the whole repertoire.
+    pub fn codepoints(&self) -> impl Iterator<Item = char> + '_ {
+        self.char_mappings().map(|(c, _)| c)
+    }
+
+    /// Whether the font maps `c` to a glyph other than `.notdef`.
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.get_glyph_id(c) != 0
+    }
+
+    /// Characters that map to `glyph_id`, for turning shaped glyphs back
+    /// into text. Scans the whole selected subtable, so callers mapping
+    /// many glyphs should build their own table from [`Self::codepoints`].
+    /// Glyphs produced by substitutions have no characters of their own.
+    pub fn chars_for_glyph(&self, glyph_id: u32) -> Vec<char> {
+        self.char_mappings().filter(|&(_, id)| id == glyph_id).map(|(c, _)| c).collect()
+    }
+}
@@ -0,0 +1,611 @@
+use crate::font::TrueTypeFont;
+use crate::shaping::{Feature, GlyphInfo, Tag};
+use crate::tables::gdef::{GdefTable, BASE_GLYPH, LIGATURE_GLYPH, MARK_GLYPH};
+use crate::Vec;
+
+pub(crate) const RIGHT_TO_LEFT: u16 = 0x0001;
+pub(crate) const IGNORE_BASE_GLYPHS: u16 = 0x0002;
+pub(crate) const IGNORE_LIGATURES: u16 = 0x0004;
+pub(crate) const IGNORE_MARKS: u16 = 0x0008;
+pub(crate) const USE_MARK_FILTERING_SET: u16 = 0x0010;
+pub(crate) const MARK_ATTACHMENT_TYPE: u16 = 0xFF00;
+
+const DEFAULT_SCRIPTS: [Tag; 3] = [*b"DFLT", *b"dflt", *b"latn"];
+
+/// Bounds-checked big-endian reads used by the layout tables. Reading past
+/// the end yields zero, which terminates any count-driven loop, so broken
+/// offsets degrade to "no data" instead of panicking.
+#[inline]
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> u16 {
+    match data.get(offset..offset + 2) {
+        Some(bytes) => u16::from_be_bytes([bytes[0], bytes[1]]),
+        None => 0,
+    }
+}
+
+#[inline]
+pub(crate) fn read_i16(data: &[u8], offset: usize) -> i16 {
+    read_u16(data, offset) as i16
+}
+
+#[inline]
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        None => 0,
+    }
+}
+
+#[inline]
+pub(crate) fn read_tag(data: &[u8], offset: usize) -> Tag {
+    match data.get(offset..offset + 4) {
+        Some(bytes) => [bytes[0], bytes[1], bytes[2], bytes[3]],
+        None => [0; 4],
+    }
+}
+
+/// Index of `glyph` in a Coverage table, if covered.
+pub(crate) fn coverage_index(data: &[u8], offset: usize, glyph: u16) -> Option<u16> {
+    match read_u16(data, offset) {
+        1 => {
+            let count = read_u16(data, offset + 2) as usize;
+            let (mut lo, mut hi) = (0, count);
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                let candidate = read_u16(data, offset + 4 + mid * 2);
+                if candidate == glyph {
+                    return Some(mid as u16);
+                } else if candidate < glyph {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            None
+        }
+
+        2 => {
+            let count = read_u16(data, offset + 2) as usize;
+            let (mut lo, mut hi) = (0, count);
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                let record = offset + 4 + mid * 6;
+                let start = read_u16(data, record);
+                let end = read_u16(data, record + 2);
+                if glyph < start {
+                    hi = mid;
+                } else if glyph > end {
+                    lo = mid + 1;
+                } else {
+                    let start_index = read_u16(data, record + 4);
+                    return Some(start_index.wrapping_add(glyph - start));
+                }
+            }
+            None
+        }
+
+        _ => None,
+    }
+}
+
+/// Class of `glyph` in a ClassDef table; unlisted glyphs are class 0.
+pub(crate) fn class_of(data: &[u8], offset: usize, glyph: u16) -> u16 {
+    match read_u16(data, offset) {
+        1 => {
+            let start = read_u16(data, offset + 2);
+            let count = read_u16(data, offset + 4);
+            if glyph >= start && glyph - start < count {
+                read_u16(data, offset + 6 + (glyph - start) as usize * 2)
+            } else {
+                0
+            }
+        }
+
+        2 => {
+            let count = read_u16(data, offset + 2) as usize;
+            let (mut lo, mut hi) = (0, count);
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                let record = offset + 4 + mid * 6;
+                let start = read_u16(data, record);
+                let end = read_u16(data, record + 2);
+                if glyph < start {
+                    hi = mid;
+                } else if glyph > end {
+                    lo = mid + 1;
+                } else {
+                    return read_u16(data, record + 4);
+                }
+            }
+            0
+        }
+
+        _ => 0,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Lookup {
+    pub(crate) lookup_type: u16,
+    pub(crate) flag: u16,
+    pub(crate) mark_filtering_set: u16,
+    pub(crate) subtables: Vec<usize>,
+}
+
+impl Lookup {
+    /// Whether the lookup flag asks for `glyph` to be skipped while matching.
+    pub(crate) fn ignores(&self, gdef: &GdefTable, glyph: u32) -> bool {
+        let glyph = glyph as u16;
+
+        match gdef.glyph_class(glyph) {
+            BASE_GLYPH => self.flag & IGNORE_BASE_GLYPHS != 0,
+            LIGATURE_GLYPH => self.flag & IGNORE_LIGATURES != 0,
+            MARK_GLYPH => {
+                if self.flag & IGNORE_MARKS != 0 {
+                    true
+                } else if self.flag & USE_MARK_FILTERING_SET != 0 {
+                    !gdef.is_in_mark_set(self.mark_filtering_set, glyph)
+                } else if self.flag & MARK_ATTACHMENT_TYPE != 0 {
+                    gdef.mark_attach_class(glyph) != self.flag >> 8
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Next glyph after `index` that the lookup does not ignore.
+    pub(crate) fn next(&self, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize) -> Option<usize> {
+        (index + 1..glyphs.len()).find(|&i| !self.ignores(gdef, glyphs[i].glyph_id))
+    }
+
+    /// Previous glyph before `index` that the lookup does not ignore.
+    pub(crate) fn prev(&self, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize) -> Option<usize> {
+        (0..index).rev().find(|&i| !self.ignores(gdef, glyphs[i].glyph_id))
+    }
+}
+
+/// How the entries of a context rule are compared against glyphs.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Matcher {
+    Glyph,
+    Class(usize),
+    Coverage(usize),
+}
+
+/// An array of `count` rule entries at `offset`, compared with `matcher`.
+#[derive(Debug, Clone, Copy)]
+struct Sequence {
+    matcher: Matcher,
+    offset: usize,
+    count: usize,
+}
+
+/// A matched context rule: the positions of its input glyphs and the
+/// sequence lookup records to apply at them.
+#[derive(Debug, Clone)]
+pub(crate) struct ContextMatch {
+    pub(crate) positions: Vec<usize>,
+    pub(crate) records: usize,
+    pub(crate) record_count: usize,
+}
+
+/// A lookup selected by the feature plan, along with the glyph mask it is
+/// restricted to and the feature value it was requested with.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PlannedLookup {
+    pub(crate) index: u16,
+    pub(crate) mask: u32,
+    pub(crate) value: u32,
+}
+
+/// Shared header of the GSUB and GPOS tables: script, feature and lookup lists.
+#[derive(Debug, Clone)]
+pub(crate) struct LayoutTable {
+    pub(crate) data: Vec<u8>,
+    pub(crate) script_list: usize,
+    pub(crate) feature_list: usize,
+    pub(crate) lookup_list: usize,
+}
+
+impl LayoutTable {
+    pub(crate) fn new() -> Self {
+        LayoutTable {
+            data: Vec::new(),
+            script_list: 0,
+            feature_list: 0,
+            lookup_list: 0,
+        }
+    }
+
+    pub(crate) fn parse(data: Vec<u8>) -> Self {
+        if data.len() < 10 || read_u16(&data, 0) != 1 {
+            return LayoutTable::new();
+        }
+
+        LayoutTable {
+            script_list: read_u16(&data, 4) as usize,
+            feature_list: read_u16(&data, 6) as usize,
+            lookup_list: read_u16(&data, 8) as usize,
+            data,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn find_script(&self, script: Tag) -> Option<usize> {
+        self.script_table(script).or_else(|| DEFAULT_SCRIPTS.iter().find_map(|&tag| self.script_table(tag)))
+    }
+
+    fn script_table(&self, tag: Tag) -> Option<usize> {
+        let count = read_u16(&self.data, self.script_list) as usize;
+
+        (0..count).find_map(|i| {
+            let record = self.script_list + 2 + i * 6;
+            if read_tag(&self.data, record) == tag {
+                Some(self.script_list + read_u16(&self.data, record + 4) as usize)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Whether the script list has an entry for exactly `script`, without
+    /// falling back to the default scripts.
+    pub(crate) fn has_script(&self, script: Tag) -> bool {
+        !self.is_empty() && self.script_table(script).is_some()
+    }
+
+    fn find_lang_sys(&self, script: usize, language: Tag) -> Option<usize> {
+        let count = read_u16(&self.data, script + 2) as usize;
+
+        for i in 0..count {
+            let record = script + 4 + i * 6;
+            if read_tag(&self.data, record) == language {
+                return Some(script + read_u16(&self.data, record + 4) as usize);
+            }
+        }
+
+        match read_u16(&self.data, script) {
+            0 => None,
+            default => Some(script + default as usize),
+        }
+    }
+
+    /// Whether the script/language system exposes `feature`.
+    pub(crate) fn has_feature(&self, script: Tag, language: Tag, feature: Tag) -> bool {
+        let mut found = false;
+        self.for_each_feature(script, language, |tag, _| found |= tag == Some(feature));
+        found
+    }
+
+    /// Calls `f` with the tag and offset of every feature in the language
+    /// system; the required feature, if any, is passed with no tag.
+    fn for_each_feature(&self, script: Tag, language: Tag, mut f: impl FnMut(Option<Tag>, usize)) {
+        if self.is_empty() {
+            return;
+        }
+
+        let Some(script) = self.find_script(script) else { return };
+        let Some(lang_sys) = self.find_lang_sys(script, language) else { return };
+
+        let feature_count = read_u16(&self.data, self.feature_list);
+        let required = read_u16(&self.data, lang_sys + 2);
+        let index_count = read_u16(&self.data, lang_sys + 4) as usize;
+
+        let indices = core::iter::once(required)
+            .chain((0..index_count).map(|i| read_u16(&self.data, lang_sys + 6 + i * 2)));
+
+        for (n, index) in indices.enumerate() {
+            if index >= feature_count {
+                continue;
+            }
+
+            let record = self.feature_list + 2 + index as usize * 6;
+            let tag = if n == 0 { None } else { Some(read_tag(&self.data, record)) };
+            let feature = self.feature_list + read_u16(&self.data, record + 4) as usize;
+            f(tag, feature);
+        }
+    }
+
+    /// Collects the lookups of the requested features for a script and
+    /// language system, sorted in lookup list order as the spec requires.
+    pub(crate) fn plan(&self, script: Tag, language: Tag, features: &[(Feature, u32)]) -> Vec<PlannedLookup> {
+        let mut planned: Vec<PlannedLookup> = Vec::new();
+
+        self.for_each_feature(script, language, |tag, feature| {
+            let (value, mask) = match tag {
+                None => (1, u32::MAX),
+                Some(tag) => match features.iter().rev().find(|(f, _)| f.tag == tag) {
+                    Some((f, mask)) if f.value != 0 => (f.value, *mask),
+                    _ => return,
+                },
+            };
+
+            let count = read_u16(&self.data, feature + 2) as usize;
+            for i in 0..count {
+                let index = read_u16(&self.data, feature + 4 + i * 2);
+                match planned.iter_mut().find(|p| p.index == index) {
+                    Some(existing) => existing.mask |= mask,
+                    None => planned.push(PlannedLookup { index, mask, value }),
+                }
+            }
+        });
+
+        planned.sort_by_key(|p| p.index);
+        planned
+    }
+
+    pub(crate) fn lookup_count(&self) -> u16 {
+        if self.is_empty() { 0 } else { read_u16(&self.data, self.lookup_list) }
+    }
+
+    /// Reads a lookup, resolving extension subtables (`extension_type`) to
+    /// the lookup type and subtable offsets they wrap.
+    pub(crate) fn lookup(&self, index: u16, extension_type: u16) -> Option<Lookup> {
+        if index >= self.lookup_count() {
+            return None;
+        }
+
+        let offset = self.lookup_list + read_u16(&self.data, self.lookup_list + 2 + index as usize * 2) as usize;
+        let mut lookup_type = read_u16(&self.data, offset);
+        let flag = read_u16(&self.data, offset + 2);
+        let count = read_u16(&self.data, offset + 4) as usize;
+        let extension = lookup_type == extension_type;
+
+        let mut subtables = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut subtable = offset + read_u16(&self.data, offset + 6 + i * 2) as usize;
+
+            if extension {
+                lookup_type = read_u16(&self.data, subtable + 2);
+                subtable += read_u32(&self.data, subtable + 4) as usize;
+            }
+
+            subtables.push(subtable);
+        }
+
+        let mark_filtering_set = if flag & USE_MARK_FILTERING_SET != 0 {
+            read_u16(&self.data, offset + 6 + count * 2)
+        } else {
+            0
+        };
+
+        Some(Lookup { lookup_type, flag, mark_filtering_set, subtables })
+    }
+}
+
+/// Context matching shared by GSUB lookup types 5/6 and GPOS lookup types 7/8.
+impl LayoutTable {
+    fn matches(&self, matcher: Matcher, entry: usize, glyph: u32) -> bool {
+        let glyph = glyph as u16;
+        match matcher {
+            Matcher::Glyph => read_u16(&self.data, entry) == glyph,
+            Matcher::Class(class_def) => class_of(&self.data, class_def, glyph) == read_u16(&self.data, entry),
+            Matcher::Coverage(base) => coverage_index(&self.data, base + read_u16(&self.data, entry) as usize, glyph).is_some(),
+        }
+    }
+
+    /// Matches the input glyphs following the glyph at `index`; `input`
+    /// describes components 1.. of a sequence of `input.count` glyphs.
+    fn match_input(&self, lookup: &Lookup, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize, input: Sequence) -> Option<Vec<usize>> {
+        let mut positions = Vec::with_capacity(input.count.max(1));
+        positions.push(index);
+
+        let mut pos = index;
+        for k in 1..input.count {
+            pos = lookup.next(gdef, glyphs, pos)?;
+            if !self.matches(input.matcher, input.offset + (k - 1) * 2, glyphs[pos].glyph_id) {
+                return None;
+            }
+            positions.push(pos);
+        }
+
+        Some(positions)
+    }
+
+    fn match_backtrack(&self, lookup: &Lookup, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize, backtrack: Sequence) -> bool {
+        let mut pos = index;
+        for k in 0..backtrack.count {
+            match lookup.prev(gdef, glyphs, pos) {
+                Some(prev) if self.matches(backtrack.matcher, backtrack.offset + k * 2, glyphs[prev].glyph_id) => pos = prev,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn match_lookahead(&self, lookup: &Lookup, gdef: &GdefTable, glyphs: &[GlyphInfo], last: usize, lookahead: Sequence) -> bool {
+        let mut pos = last;
+        for k in 0..lookahead.count {
+            match lookup.next(gdef, glyphs, pos) {
+                Some(next) if self.matches(lookahead.matcher, lookahead.offset + k * 2, glyphs[next].glyph_id) => pos = next,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Sequence context subtable (GSUB type 5, GPOS type 7), formats 1-3.
+    pub(crate) fn match_context(&self, lookup: &Lookup, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize, subtable: usize) -> Option<ContextMatch> {
+        let data = &self.data;
+        let glyph = glyphs[index].glyph_id as u16;
+
+        let format = read_u16(data, subtable);
+        if format == 3 {
+            let count = read_u16(data, subtable + 2) as usize;
+            let record_count = read_u16(data, subtable + 4) as usize;
+            let coverages = subtable + 6;
+
+            if count == 0 || !self.matches(Matcher::Coverage(subtable), coverages, glyph as u32) {
+                return None;
+            }
+
+            let positions = self.match_input(lookup, gdef, glyphs, index, Sequence { matcher: Matcher::Coverage(subtable), offset: coverages + 2, count })?;
+            return Some(ContextMatch { positions, records: coverages + count * 2, record_count });
+        }
+
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+        let (matcher, set_index, sets) = match format {
+            1 => (Matcher::Glyph, coverage, subtable + 4),
+            2 => {
+                let class_def = subtable + read_u16(data, subtable + 4) as usize;
+                (Matcher::Class(class_def), class_of(data, class_def, glyph), subtable + 6)
+            }
+            _ => return None,
+        };
+
+        if set_index >= read_u16(data, sets) {
+            return None;
+        }
+
+        let set = match read_u16(data, sets + 2 + set_index as usize * 2) {
+            0 => return None,
+            offset => subtable + offset as usize,
+        };
+
+        for r in 0..read_u16(data, set) as usize {
+            let rule = set + read_u16(data, set + 2 + r * 2) as usize;
+            let count = read_u16(data, rule) as usize;
+            let record_count = read_u16(data, rule + 2) as usize;
+
+            if count == 0 {
+                continue;
+            }
+
+            if let Some(positions) = self.match_input(lookup, gdef, glyphs, index, Sequence { matcher, offset: rule + 4, count }) {
+                return Some(ContextMatch { positions, records: rule + 4 + (count - 1) * 2, record_count });
+            }
+        }
+
+        None
+    }
+
+    /// Chained sequence context subtable (GSUB type 6, GPOS type 8), formats 1-3.
+    pub(crate) fn match_chained_context(&self, lookup: &Lookup, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize, subtable: usize) -> Option<ContextMatch> {
+        let data = &self.data;
+        let glyph = glyphs[index].glyph_id as u16;
+
+        let format = read_u16(data, subtable);
+        if format == 3 {
+            let backtrack_count = read_u16(data, subtable + 2) as usize;
+            let backtrack = subtable + 4;
+            let input_count = read_u16(data, backtrack + backtrack_count * 2) as usize;
+            let input = backtrack + backtrack_count * 2 + 2;
+            let lookahead_count = read_u16(data, input + input_count * 2) as usize;
+            let lookahead = input + input_count * 2 + 2;
+            let record_count = read_u16(data, lookahead + lookahead_count * 2) as usize;
+            let records = lookahead + lookahead_count * 2 + 2;
+
+            let matcher = Matcher::Coverage(subtable);
+            if input_count == 0 || !self.matches(matcher, input, glyph as u32) {
+                return None;
+            }
+
+            let positions = self.match_input(lookup, gdef, glyphs, index, Sequence { matcher, offset: input + 2, count: input_count })?;
+            let last = *positions.last()?;
+
+            if !self.match_backtrack(lookup, gdef, glyphs, index, Sequence { matcher, offset: backtrack, count: backtrack_count })
+                || !self.match_lookahead(lookup, gdef, glyphs, last, Sequence { matcher, offset: lookahead, count: lookahead_count }) {
+                return None;
+            }
+
+            return Some(ContextMatch { positions, records, record_count });
+        }
+
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+        let (matchers, set_index, sets) = match format {
+            1 => ([Matcher::Glyph; 3], coverage, subtable + 4),
+            2 => {
+                let class_def = |at: usize| subtable + read_u16(data, at) as usize;
+                let (backtrack, input, lookahead) = (class_def(subtable + 4), class_def(subtable + 6), class_def(subtable + 8));
+                (
+                    [Matcher::Class(backtrack), Matcher::Class(input), Matcher::Class(lookahead)],
+                    class_of(data, input, glyph),
+                    subtable + 10,
+                )
+            }
+            _ => return None,
+        };
+
+        if set_index >= read_u16(data, sets) {
+            return None;
+        }
+
+        let set = match read_u16(data, sets + 2 + set_index as usize * 2) {
+            0 => return None,
+            offset => subtable + offset as usize,
+        };
+
+        for r in 0..read_u16(data, set) as usize {
+            let rule = set + read_u16(data, set + 2 + r * 2) as usize;
+            let backtrack_count = read_u16(data, rule) as usize;
+            let backtrack = rule + 2;
+            let input_count = read_u16(data, backtrack + backtrack_count * 2) as usize;
+            let input = backtrack + backtrack_count * 2 + 2;
+            let lookahead_count = read_u16(data, input + input_count.saturating_sub(1) * 2) as usize;
+            let lookahead = input + input_count.saturating_sub(1) * 2 + 2;
+            let record_count = read_u16(data, lookahead + lookahead_count * 2) as usize;
+            let records = lookahead + lookahead_count * 2 + 2;
+
+            if input_count == 0 {
+                continue;
+            }
+
+            let Some(positions) = self.match_input(lookup, gdef, glyphs, index, Sequence { matcher: matchers[1], offset: input, count: input_count }) else { continue };
+            let last = positions[positions.len() - 1];
+
+            if self.match_backtrack(lookup, gdef, glyphs, index, Sequence { matcher: matchers[0], offset: backtrack, count: backtrack_count })
+                && self.match_lookahead(lookup, gdef, glyphs, last, Sequence { matcher: matchers[2], offset: lookahead, count: lookahead_count }) {
+                return Some(ContextMatch { positions, records, record_count });
+            }
+        }
+
+        None
+    }
+
+    /// Reverse chaining single substitution (GSUB type 8): returns the
+    /// replacement for the glyph at `index` if its context matches.
+    pub(crate) fn match_reverse_chained(&self, lookup: &Lookup, gdef: &GdefTable, glyphs: &[GlyphInfo], index: usize, subtable: usize) -> Option<u32> {
+        let data = &self.data;
+        if read_u16(data, subtable) != 1 {
+            return None;
+        }
+
+        let glyph = glyphs[index].glyph_id as u16;
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+
+        let backtrack_count = read_u16(data, subtable + 4) as usize;
+        let backtrack = subtable + 6;
+        let lookahead_count = read_u16(data, backtrack + backtrack_count * 2) as usize;
+        let lookahead = backtrack + backtrack_count * 2 + 2;
+        let substitute_count = read_u16(data, lookahead + lookahead_count * 2);
+        let substitutes = lookahead + lookahead_count * 2 + 2;
+
+        let matcher = Matcher::Coverage(subtable);
+        if coverage >= substitute_count
+            || !self.match_backtrack(lookup, gdef, glyphs, index, Sequence { matcher, offset: backtrack, count: backtrack_count })
+            || !self.match_lookahead(lookup, gdef, glyphs, index, Sequence { matcher, offset: lookahead, count: lookahead_count }) {
+            return None;
+        }
+
+        Some(read_u16(data, substitutes + coverage as usize * 2) as u32)
+    }
+}
+
+impl TrueTypeFont {
+    pub(crate) fn table_data(&self, tag: &[u8], font_bytes: &[u8]) -> Option<Vec<u8>> {
+        let table = self.tables.iter().find(|t| t.table_tag == tag)?;
+        let start = table.offset as usize;
+        let end = start.checked_add(table.length as usize)?.min(font_bytes.len());
+
+        if start >= end {
+            return None;
+        }
+
+        Some(font_bytes[start..end].to_vec())
+    }
+}
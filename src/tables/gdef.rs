@@ -0,0 +1,73 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::{class_of, coverage_index, read_u16, read_u32};
+use crate::Vec;
+
+pub(crate) const BASE_GLYPH: u16 = 1;
+pub(crate) const LIGATURE_GLYPH: u16 = 2;
+pub(crate) const MARK_GLYPH: u16 = 3;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GdefTable {
+    pub(crate) data: Vec<u8>,
+    pub(crate) glyph_class_def: usize,
+    pub(crate) mark_attach_class_def: usize,
+    pub(crate) mark_glyph_sets_def: usize,
+}
+
+impl GdefTable {
+    pub(crate) fn new() -> Self {
+        GdefTable {
+            data: Vec::new(),
+            glyph_class_def: 0,
+            mark_attach_class_def: 0,
+            mark_glyph_sets_def: 0,
+        }
+    }
+
+    /// GDEF glyph class: 1 base, 2 ligature, 3 mark, 4 component, 0 unknown.
+    pub(crate) fn glyph_class(&self, glyph: u16) -> u16 {
+        if self.glyph_class_def == 0 {
+            return 0;
+        }
+        class_of(&self.data, self.glyph_class_def, glyph)
+    }
+
+    pub(crate) fn mark_attach_class(&self, glyph: u16) -> u16 {
+        if self.mark_attach_class_def == 0 {
+            return 0;
+        }
+        class_of(&self.data, self.mark_attach_class_def, glyph)
+    }
+
+    pub(crate) fn is_in_mark_set(&self, set: u16, glyph: u16) -> bool {
+        if self.mark_glyph_sets_def == 0 {
+            return false;
+        }
+
+        let base = self.mark_glyph_sets_def;
+        if set >= read_u16(&self.data, base + 2) {
+            return false;
+        }
+
+        let coverage = base + read_u32(&self.data, base + 4 + set as usize * 4) as usize;
+        coverage_index(&self.data, coverage, glyph).is_some()
+    }
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_gdef(&mut self, font_bytes: &[u8]) {
+        let Some(data) = self.table_data(b"GDEF", font_bytes) else { return };
+        if data.len() < 12 || read_u16(&data, 0) != 1 {
+            return;
+        }
+
+        let minor_version = read_u16(&data, 2);
+
+        self.gdef = GdefTable {
+            glyph_class_def: read_u16(&data, 4) as usize,
+            mark_attach_class_def: read_u16(&data, 10) as usize,
+            mark_glyph_sets_def: if minor_version >= 2 { read_u16(&data, 12) as usize } else { 0 },
+            data,
+        };
+    }
+}
@@ -1,6 +1,5 @@
 use crate::font::{get_i16_be, get_u16_be, TrueTypeFont};
 use crate::rasterizer::point::Contour;
-use crate::tables::cmap::SupportedCmapFormats::{Format0, Format12, Format4, Format6};
 use crate::tables::glyf::ProtoGlyph::{Composite, Simple};
 use crate::tables::loca::LocaTable;
 
@@ -10,10 +9,13 @@ pub(crate) const WE_HAVE_A_SCALE: u16 = 0x0008;
 pub(crate) const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
 pub(crate) const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
 pub(crate) const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+pub(crate) const ROUND_XY_TO_GRID: u16 = 0x0004;
+pub(crate) const SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+pub(crate) const UNSCALED_COMPONENT_OFFSET: u16 = 0x1000;
 const ARGS_ARE_WORDS: u16 = 0x0001;
 const MORE_COMPONENTS: u16 = 0x0020;
 const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
-const ROUND_XY_TO_GRID: u16 = 0x0004;
+const USE_MY_METRICS: u16 = 0x0200;
 
 #[derive(Debug, Clone)]
 pub(crate) struct SimpleGlyph {
@@ -48,7 +50,10 @@ pub(crate) struct CompositeGlyph {
 pub(crate) struct CompositeComponent {
     pub(crate) flags: u16,
     pub(crate) glyph_index: u16,
+    /// An x offset, or without `ARGS_ARE_XY_VALUES` the number of the
+    /// point in the glyph so far to align with.
     pub(crate) argument1: i16,
+    /// A y offset, or the number of the component point to align.
     pub(crate) argument2: i16,
     pub(crate) scale: Option<f32>,
     pub(crate) x_scale: Option<f32>,
@@ -147,37 +152,32 @@ impl ProtoGlyph {
 }
 
 impl TrueTypeFont {
-    pub(crate) fn load_glyf(&mut self) {
-        for table in &self.tables {
-            if table.table_tag == "glyf".as_bytes() {
-                self.glyf = *table;
-
-                return;
-            }
+    pub(crate) fn load_glyf(&mut self, font_bytes: &[u8]) {
+        match self.table_data(b"glyf", font_bytes) {
+            Some(data) => self.glyf_data = data,
+            None => panic!("GLYF table not found"),
         }
-
-        panic!("GLYF table not found");
     }
 
-    pub(crate) fn get_glyph(&self, font_bytes: &[u8], glyph_id: u32) -> ProtoGlyph {
-        let (start_offset, end_offset) = match &self.loca {
-            LocaTable::Short(offsets, ..) => {
-                let start = offsets[glyph_id as usize] as u32 * 2;
-                let end = offsets[glyph_id as usize + 1] as u32 * 2;
-                (start, end)
-            },
-
-            LocaTable::Long(offsets) => {
-                let start = offsets[glyph_id as usize];
-                let end = offsets[glyph_id as usize + 1];
-                (start, end)
-            }
+    /// Start and end of a glyph's data within `glyf`, or `None` for glyph
+    /// ids past `loca` and offsets past the table.
+    fn glyph_range(&self, glyph_id: u32) -> Option<(usize, usize)> {
+        let idx = glyph_id as usize;
+
+        let (start, end) = match &self.loca {
+            LocaTable::Short(offsets) => (*offsets.get(idx)? as usize * 2, *offsets.get(idx + 1)? as usize * 2),
+            LocaTable::Long(offsets) => (*offsets.get(idx)? as usize, *offsets.get(idx + 1)? as usize),
         };
 
-        let glyf_length = end_offset as usize - start_offset as usize;
-        let glyf_offset = self.glyf.offset as usize + start_offset as usize;
+        (start <= end && end <= self.glyf_data.len()).then_some((start, end))
+    }
 
-        if glyf_length == 0 { return ProtoGlyph::Empty; }
+    pub(crate) fn get_glyph(&self, glyph_id: u32) -> ProtoGlyph {
+        let Some((glyf_offset, end_offset)) = self.glyph_range(glyph_id) else { return ProtoGlyph::Empty };
+        let glyf_length = end_offset - glyf_offset;
+        let font_bytes = &self.glyf_data[..];
+
+        if glyf_length < 10 { return ProtoGlyph::Empty; }
 
         let contours = get_i16_be(font_bytes, glyf_offset);
 
@@ -322,10 +322,15 @@ impl TrueTypeFont {
                     component.argument1 = get_i16_be(font_bytes, offset);
                     component.argument2 = get_i16_be(font_bytes, offset + 2);
                     offset += 4;
-                } else {
+                } else if flags & ARGS_ARE_XY_VALUES != 0 {
                     component.argument1 = font_bytes[offset] as i8 as i16;
                     component.argument2 = font_bytes[offset + 1] as i8 as i16;
                     offset += 2;
+                } else {
+                    // Point numbers are unsigned
+                    component.argument1 = font_bytes[offset] as i16;
+                    component.argument2 = font_bytes[offset + 1] as i16;
+                    offset += 2;
                 }
 
                 if flags & WE_HAVE_A_SCALE != 0 {
@@ -363,91 +368,42 @@ impl TrueTypeFont {
         }
     }
 
-    pub(crate) fn cache_all_glyphs(&mut self, font_bytes: &[u8]) {
-        match &self.cmap.subtables[0] {
-
-            Format0 { data, .. } => {
-
-                let mut glyph_data = self.get_glyph(font_bytes, 0);
-                self.glyph_data_table.insert(0, self.load_points(&mut glyph_data, &self, &font_bytes));
-
-                for codepoint in 0..256 {
-                    if let Some(ch) = char::from_u32(codepoint) {
-                        let glyph_id = data.glyph_id_array[codepoint as usize] as u32;
-                        let mut glyph_data = self.get_glyph(font_bytes, glyph_id);
-
-                        if glyph_id != 0 {
-                            self.glyph_id_table.insert(ch, glyph_id);
-                            self.glyph_data_table.insert(glyph_id, self.load_points(&mut glyph_data, &self, &font_bytes));
-                        }
-                    }
-                }
-            }
-
-            Format4 { data, ..} => {
-
-                let mut glyph_data = self.get_glyph(font_bytes, 0);
-                self.glyph_data_table.insert(0, self.load_points(&mut glyph_data, &self, &font_bytes));
-
-                for seg_idx in 0..data.seg_count_x2 / 2 {
-                    let start = data.start_count[seg_idx as usize];
-                    let end = data.end_count[seg_idx as usize];
-
-                    for codepoint in start..=end {
-                        if let Some(ch) = char::from_u32(codepoint as u32) {
-                            let glyph_id = self.get_glyph_id(ch);
-                            let mut glyph_data = self.get_glyph(font_bytes, glyph_id);
-
-                            if glyph_id != 0 {
-                                self.glyph_id_table.insert(ch, glyph_id);
-                                self.glyph_data_table.insert(glyph_id, self.load_points(&mut glyph_data, &self, &font_bytes));
-                            }
-                        }
-                    }
-                }
-            }
-
-            Format6 { data, .. } => {
-
-                let mut glyph_data = self.get_glyph(font_bytes, 0);
-                self.glyph_data_table.insert(0, self.load_points(&mut glyph_data, &self, &font_bytes));
-
-                for i in 0..data.entry_count {
-                    let codepoint = data.first_code + i;
-                    if let Some(ch) = char::from_u32(codepoint as u32) {
-                        let glyph_id = data.glyph_id_array[i as usize] as u32;
-                        let mut glyph_data = self.get_glyph(font_bytes, glyph_id);
-
-                        if glyph_id != 0 {
-                            self.glyph_id_table.insert(ch, glyph_id);
-                            self.glyph_data_table.insert(glyph_id, self.load_points(&mut glyph_data, &self, &font_bytes));
-                        }
-                    }
-                }
-            }
-
-            Format12 { data, .. } => {
+    /// Bounding box of a glyph from its `glyf` header as `(x_min, y_min,
+    /// x_max, y_max)`, without parsing the outline. `None` for glyphs with
+    /// no outline.
+    pub(crate) fn glyph_bounds(&self, glyph_id: u32) -> Option<(i16, i16, i16, i16)> {
+        let (start, end) = self.glyph_range(glyph_id)?;
+        if end - start < 10 {
+            return None;
+        }
 
-                let mut glyph_data = self.get_glyph(font_bytes, 0);
-                self.glyph_data_table.insert(0, self.load_points(&mut glyph_data, &self, &font_bytes));
+        let data = &self.glyf_data;
+        Some((get_i16_be(data, start + 2), get_i16_be(data, start + 4), get_i16_be(data, start + 6), get_i16_be(data, start + 8)))
+    }
 
-                for group in &data.groups {
-                    for codepoint in group.start_char_code..=group.end_char_code {
-                        if let Some(ch) = char::from_u32(codepoint) {
-                            let offset = (codepoint - group.start_char_code) as usize;
-                            let glyph_id = group.start_glyph_id + offset as u32;
-                            let mut glyph_data = self.get_glyph(font_bytes, glyph_id);
+    /// The component a composite glyph takes its horizontal metrics from,
+    /// from its `USE_MY_METRICS` flag. Simple glyphs are not parsed.
+    pub(crate) fn use_my_metrics(&self, glyph_id: u32) -> Option<u32> {
+        let (start, end) = self.glyph_range(glyph_id)?;
+        if end - start < 10 || get_i16_be(&self.glyf_data, start) >= 0 {
+            return None;
+        }
 
-                            if glyph_id != 0 {
-                                self.glyph_id_table.insert(ch, glyph_id);
-                                self.glyph_data_table.insert(glyph_id, self.load_points(&mut glyph_data, &self, &font_bytes));
-                            }
-                        }
-                    }
-                }
-            }
+        match self.get_glyph(glyph_id) {
+            Composite(glyph) => glyph.components.iter()
+                .find(|component| component.flags & USE_MY_METRICS != 0)
+                .map(|component| component.glyph_index as u32),
+            _ => None,
+        }
+    }
 
-            _ => {},
+    /// Parses a glyph's outline into `glyph_data_table` the first time it is
+    /// needed, so load time does not grow with the number of glyphs.
+    pub(crate) fn load_outline(&mut self, glyph_id: u32) {
+        if !self.glyph_data_table.contains_key(&glyph_id) {
+            let mut glyph_data = self.get_glyph(glyph_id);
+            let glyph = self.load_points(glyph_id, &mut glyph_data);
+            self.glyph_data_table.insert(glyph_id, glyph);
         }
     }
-}
\ No newline at end of file
+}
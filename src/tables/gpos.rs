@@ -0,0 +1,526 @@
+use crate::font::TrueTypeFont;
+use crate::shaping::{Feature, GlyphBuffer, GlyphPosition, Tag};
+use crate::shaping::buffer::GLOBAL_MASK;
+use crate::tables::common::{class_of, coverage_index, read_i16, read_u16, ContextMatch, LayoutTable, Lookup, PlannedLookup, RIGHT_TO_LEFT};
+use crate::tables::gdef::MARK_GLYPH;
+use crate::tables::gsub::MAX_NESTING_LEVEL;
+use crate::Vec;
+
+const SINGLE_ADJUSTMENT: u16 = 1;
+const PAIR_ADJUSTMENT: u16 = 2;
+const CURSIVE: u16 = 3;
+const MARK_TO_BASE: u16 = 4;
+const MARK_TO_LIGATURE: u16 = 5;
+const MARK_TO_MARK: u16 = 6;
+const CONTEXT: u16 = 7;
+const CHAINED_CONTEXT: u16 = 8;
+const EXTENSION: u16 = 9;
+
+pub(crate) const ATTACH_MARK: u8 = 1;
+pub(crate) const ATTACH_CURSIVE: u8 = 2;
+
+// ValueRecord format bits
+const X_PLACEMENT: u16 = 0x0001;
+const Y_PLACEMENT: u16 = 0x0002;
+const X_ADVANCE: u16 = 0x0004;
+const Y_ADVANCE: u16 = 0x0008;
+
+impl TrueTypeFont {
+    pub(crate) fn load_gpos(&mut self, font_bytes: &[u8]) {
+        if let Some(data) = self.table_data(b"GPOS", font_bytes) {
+            self.gpos = LayoutTable::parse(data);
+        }
+    }
+
+    /// Positions every glyph in `buffer` with the GPOS lookups of `features`.
+    ///
+    /// Advances start out as the `hmtx` advances, marks (by GDEF class) get
+    /// zero advance, and attached glyphs receive offsets relative to their
+    /// own pen position. All values are in font units.
+    pub fn apply_gpos(&self, buffer: &mut GlyphBuffer, script: Tag, language: Tag, features: &[Feature]) {
+        let features: Vec<(Feature, u32)> = features.iter().map(|&f| (f, GLOBAL_MASK)).collect();
+        let plan = self.gpos.plan(script, language, &features);
+
+        self.init_positions(buffer);
+        self.apply_gpos_plan(buffer, &plan);
+        self.finish_positions(buffer);
+    }
+
+    /// Whether the font's GPOS table offers `feature` for the script and language.
+    pub fn has_gpos_feature(&self, script: Tag, language: Tag, feature: Tag) -> bool {
+        self.gpos.has_feature(script, language, feature)
+    }
+
+    /// Starts every glyph at its `hmtx` advance or, in vertical text, at
+    /// its vertical advance, with offsets that move the horizontal origin
+    /// to the vertical one.
+    pub(crate) fn init_positions(&self, buffer: &mut GlyphBuffer) {
+        buffer.positions.clear();
+        for info in &buffer.glyphs {
+            let position = if buffer.direction.is_vertical() {
+                let metrics = self.vertical_metrics(info.glyph_id);
+                GlyphPosition {
+                    y_advance: -(metrics.advance_height as i32),
+                    x_offset: -(metrics.origin_x as i32),
+                    y_offset: -(metrics.origin_y as i32),
+                    ..GlyphPosition::default()
+                }
+            } else {
+                GlyphPosition {
+                    x_advance: self.h_metric(info.glyph_id).0 as i32,
+                    ..GlyphPosition::default()
+                }
+            };
+
+            buffer.positions.push(position);
+        }
+    }
+
+    /// Zeroes mark advances and converts attachment chains into offsets.
+    pub(crate) fn finish_positions(&self, buffer: &mut GlyphBuffer) {
+        for (info, pos) in buffer.glyphs.iter().zip(buffer.positions.iter_mut()) {
+            if self.gdef.glyph_class(info.glyph_id as u16) == MARK_GLYPH {
+                pos.x_advance = 0;
+                pos.y_advance = 0;
+            }
+        }
+
+        let rtl = buffer.direction.is_rtl();
+        let mut resolved = Vec::new();
+        resolved.resize(buffer.positions.len(), false);
+
+        for i in 0..buffer.positions.len() {
+            resolve_attachment(&mut buffer.positions, &mut resolved, i, rtl, 0);
+        }
+    }
+
+    pub(crate) fn apply_gpos_plan(&self, buffer: &mut GlyphBuffer, plan: &[PlannedLookup]) {
+        for planned in plan {
+            let Some(lookup) = self.gpos.lookup(planned.index, EXTENSION) else { continue };
+
+            for i in 0..buffer.glyphs.len() {
+                let info = buffer.glyphs[i];
+                if info.mask & planned.mask == 0 || lookup.ignores(&self.gdef, info.glyph_id) {
+                    continue;
+                }
+
+                self.position_at(&lookup, buffer, i, 0);
+            }
+        }
+    }
+
+    pub(crate) fn position_at(&self, lookup: &Lookup, buffer: &mut GlyphBuffer, index: usize, depth: usize) -> bool {
+        for &subtable in &lookup.subtables {
+            let applied = match lookup.lookup_type {
+                SINGLE_ADJUSTMENT => self.single_adjustment(subtable, buffer, index),
+                PAIR_ADJUSTMENT => self.pair_adjustment(lookup, subtable, buffer, index),
+                CURSIVE => self.cursive_attachment(lookup, subtable, buffer, index),
+                MARK_TO_BASE => self.mark_to_base(lookup, subtable, buffer, index),
+                MARK_TO_LIGATURE => self.mark_to_ligature(lookup, subtable, buffer, index),
+                MARK_TO_MARK => self.mark_to_mark(lookup, subtable, buffer, index),
+                CONTEXT => self.gpos.match_context(lookup, &self.gdef, &buffer.glyphs, index, subtable)
+                    .map(|m| self.apply_nested_positioning(buffer, m, depth))
+                    .is_some(),
+                CHAINED_CONTEXT => self.gpos.match_chained_context(lookup, &self.gdef, &buffer.glyphs, index, subtable)
+                    .map(|m| self.apply_nested_positioning(buffer, m, depth))
+                    .is_some(),
+                _ => false,
+            };
+
+            if applied {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn apply_nested_positioning(&self, buffer: &mut GlyphBuffer, matched: ContextMatch, depth: usize) {
+        if depth >= MAX_NESTING_LEVEL {
+            return;
+        }
+
+        for r in 0..matched.record_count {
+            let record = matched.records + r * 4;
+            let sequence_index = read_u16(&self.gpos.data, record) as usize;
+            let lookup_index = read_u16(&self.gpos.data, record + 2);
+
+            let Some(&pos) = matched.positions.get(sequence_index) else { continue };
+            let Some(nested) = self.gpos.lookup(lookup_index, EXTENSION) else { continue };
+
+            self.position_at(&nested, buffer, pos, depth + 1);
+        }
+    }
+
+    /// Size in bytes of a ValueRecord with the given format.
+    fn value_record_size(format: u16) -> usize {
+        (format & 0x00FF).count_ones() as usize * 2
+    }
+
+    /// Adds a ValueRecord to a glyph position. Device table adjustments are
+    /// only meaningful for hinted rendering and are skipped.
+    fn apply_value_record(&self, record: usize, format: u16, pos: &mut GlyphPosition) {
+        let data = &self.gpos.data;
+        let mut offset = record;
+
+        for (bit, field) in [
+            (X_PLACEMENT, &mut pos.x_offset),
+            (Y_PLACEMENT, &mut pos.y_offset),
+            (X_ADVANCE, &mut pos.x_advance),
+            (Y_ADVANCE, &mut pos.y_advance),
+        ] {
+            if format & bit != 0 {
+                *field += read_i16(data, offset) as i32;
+                offset += 2;
+            }
+        }
+    }
+
+    fn single_adjustment(&self, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> bool {
+        let data = &self.gpos.data;
+        let glyph = buffer.glyphs[index].glyph_id as u16;
+        let Some(coverage) = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph) else { return false };
+        let format = read_u16(data, subtable + 4);
+
+        let record = match read_u16(data, subtable) {
+            1 => subtable + 6,
+            2 => {
+                if coverage >= read_u16(data, subtable + 6) {
+                    return false;
+                }
+                subtable + 8 + coverage as usize * Self::value_record_size(format)
+            }
+            _ => return false,
+        };
+
+        self.apply_value_record(record, format, &mut buffer.positions[index]);
+        true
+    }
+
+    fn pair_adjustment(&self, lookup: &Lookup, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> bool {
+        let data = &self.gpos.data;
+        let first = buffer.glyphs[index].glyph_id as u16;
+        let Some(coverage) = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, first) else { return false };
+        let Some(next) = lookup.next(&self.gdef, &buffer.glyphs, index) else { return false };
+        let second = buffer.glyphs[next].glyph_id as u16;
+
+        let format1 = read_u16(data, subtable + 4);
+        let format2 = read_u16(data, subtable + 6);
+        let size1 = Self::value_record_size(format1);
+        let size2 = Self::value_record_size(format2);
+
+        let record = match read_u16(data, subtable) {
+            1 => {
+                if coverage >= read_u16(data, subtable + 8) {
+                    return false;
+                }
+
+                let set = subtable + read_u16(data, subtable + 10 + coverage as usize * 2) as usize;
+                let stride = 2 + size1 + size2;
+                let (mut lo, mut hi) = (0, read_u16(data, set) as usize);
+                let mut found = None;
+
+                while lo < hi {
+                    let mid = (lo + hi) / 2;
+                    let record = set + 2 + mid * stride;
+                    let candidate = read_u16(data, record);
+                    if candidate == second {
+                        found = Some(record + 2);
+                        break;
+                    } else if candidate < second {
+                        lo = mid + 1;
+                    } else {
+                        hi = mid;
+                    }
+                }
+
+                match found {
+                    Some(record) => record,
+                    None => return false,
+                }
+            }
+
+            2 => {
+                let class1 = class_of(data, subtable + read_u16(data, subtable + 8) as usize, first);
+                let class2 = class_of(data, subtable + read_u16(data, subtable + 10) as usize, second);
+                let class1_count = read_u16(data, subtable + 12);
+                let class2_count = read_u16(data, subtable + 14);
+
+                if class1 >= class1_count || class2 >= class2_count {
+                    return false;
+                }
+
+                subtable + 16 + (class1 as usize * class2_count as usize + class2 as usize) * (size1 + size2)
+            }
+
+            _ => return false,
+        };
+
+        self.apply_value_record(record, format1, &mut buffer.positions[index]);
+        self.apply_value_record(record + size1, format2, &mut buffer.positions[next]);
+        true
+    }
+
+    /// Entry or exit anchor (`which` 0 or 1) of a covered glyph.
+    fn entry_exit_anchor(&self, subtable: usize, glyph: u32, which: usize) -> Option<usize> {
+        let data = &self.gpos.data;
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph as u16)?;
+        if coverage >= read_u16(data, subtable + 4) {
+            return None;
+        }
+
+        match read_u16(data, subtable + 6 + coverage as usize * 4 + which * 2) {
+            0 => None,
+            offset => Some(subtable + offset as usize),
+        }
+    }
+
+    /// Connects the exit anchor of the previous glyph with the entry anchor
+    /// of the glyph at `index`.
+    fn cursive_attachment(&self, lookup: &Lookup, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> bool {
+        if read_u16(&self.gpos.data, subtable) != 1 {
+            return false;
+        }
+
+        let Some(entry) = self.entry_exit_anchor(subtable, buffer.glyphs[index].glyph_id, 0) else { return false };
+        let Some(prev) = lookup.prev(&self.gdef, &buffer.glyphs, index) else { return false };
+        let Some(exit) = self.entry_exit_anchor(subtable, buffer.glyphs[prev].glyph_id, 1) else { return false };
+
+        let (entry_x, entry_y) = self.anchor(entry);
+        let (exit_x, exit_y) = self.anchor(exit);
+        let positions = &mut buffer.positions;
+
+        if buffer.direction.is_rtl() {
+            let d = exit_x + positions[prev].x_offset;
+            positions[prev].x_advance -= d;
+            positions[prev].x_offset -= d;
+            positions[index].x_advance = entry_x + positions[index].x_offset;
+        } else {
+            positions[prev].x_advance = exit_x + positions[prev].x_offset;
+            let d = entry_x + positions[index].x_offset;
+            positions[index].x_advance -= d;
+            positions[index].x_offset -= d;
+        }
+
+        // The child glyph aligns itself vertically against its parent; with
+        // the RIGHT_TO_LEFT flag the last glyph of the chain stays on the baseline
+        let (child, parent, y_offset) = if lookup.flag & RIGHT_TO_LEFT != 0 {
+            (prev, index, entry_y - exit_y)
+        } else {
+            (index, prev, exit_y - entry_y)
+        };
+
+        // Break a chain that would point straight back at this glyph
+        if positions[parent].attach_type == ATTACH_CURSIVE
+            && parent.checked_add_signed(positions[parent].attach_chain as isize) == Some(child) {
+            positions[parent].attach_chain = 0;
+            positions[parent].attach_type = 0;
+        }
+
+        positions[child].attach_chain = (parent as isize - child as isize) as i16;
+        positions[child].attach_type = ATTACH_CURSIVE;
+        positions[child].y_offset = y_offset;
+        true
+    }
+
+    fn is_mark(&self, glyph: u32) -> bool {
+        self.gdef.glyph_class(glyph as u16) == MARK_GLYPH
+    }
+
+    /// Anchor coordinates in font units. Format 2 contour points and format 3
+    /// device tables only matter for hinted rendering, so every format
+    /// resolves to its design coordinates.
+    fn anchor(&self, offset: usize) -> (i32, i32) {
+        let data = &self.gpos.data;
+        (read_i16(data, offset + 2) as i32, read_i16(data, offset + 4) as i32)
+    }
+
+    /// Mark class and anchor of `glyph` in a MarkArray, given its coverage index.
+    fn mark_record(&self, mark_array: usize, index: u16) -> Option<(u16, usize)> {
+        let data = &self.gpos.data;
+        if index >= read_u16(data, mark_array) {
+            return None;
+        }
+
+        let record = mark_array + 2 + index as usize * 4;
+        let anchor = read_u16(data, record + 2) as usize;
+        if anchor == 0 {
+            return None;
+        }
+
+        Some((read_u16(data, record), mark_array + anchor))
+    }
+
+    /// Anchor for `class` in row `row` of a BaseArray-style anchor matrix.
+    fn anchor_in_matrix(&self, matrix: usize, row: u16, class: u16, class_count: u16) -> Option<usize> {
+        let data = &self.gpos.data;
+        if row >= read_u16(data, matrix) || class >= class_count {
+            return None;
+        }
+
+        let entry = matrix + 2 + (row as usize * class_count as usize + class as usize) * 2;
+        match read_u16(data, entry) {
+            0 => None,
+            offset => Some(matrix + offset as usize),
+        }
+    }
+
+    fn attach_mark(&self, buffer: &mut GlyphBuffer, mark: usize, mark_anchor: usize, base: usize, base_anchor: usize) {
+        let (mark_x, mark_y) = self.anchor(mark_anchor);
+        let (base_x, base_y) = self.anchor(base_anchor);
+
+        let pos = &mut buffer.positions[mark];
+        pos.x_offset = base_x - mark_x;
+        pos.y_offset = base_y - mark_y;
+        pos.attach_chain = (base as isize - mark as isize) as i16;
+        pos.attach_type = ATTACH_MARK;
+    }
+
+    fn mark_to_base(&self, lookup: &Lookup, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> bool {
+        let data = &self.gpos.data;
+        if read_u16(data, subtable) != 1 {
+            return false;
+        }
+
+        let glyph = buffer.glyphs[index].glyph_id;
+        let Some(mark_index) = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph as u16) else { return false };
+
+        // The base is the closest preceding glyph that is not a mark
+        let Some(base) = (0..index).rev().find(|&j| {
+            let id = buffer.glyphs[j].glyph_id;
+            !self.is_mark(id) && !lookup.ignores(&self.gdef, id)
+        }) else { return false };
+
+        let Some(base_index) = coverage_index(data, subtable + read_u16(data, subtable + 4) as usize, buffer.glyphs[base].glyph_id as u16) else { return false };
+
+        let class_count = read_u16(data, subtable + 6);
+        let mark_array = subtable + read_u16(data, subtable + 8) as usize;
+        let base_array = subtable + read_u16(data, subtable + 10) as usize;
+
+        let Some((class, mark_anchor)) = self.mark_record(mark_array, mark_index) else { return false };
+        let Some(base_anchor) = self.anchor_in_matrix(base_array, base_index, class, class_count) else { return false };
+
+        self.attach_mark(buffer, index, mark_anchor, base, base_anchor);
+        true
+    }
+
+    fn mark_to_ligature(&self, lookup: &Lookup, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> bool {
+        let data = &self.gpos.data;
+        if read_u16(data, subtable) != 1 {
+            return false;
+        }
+
+        let mark = buffer.glyphs[index];
+        let Some(mark_index) = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, mark.glyph_id as u16) else { return false };
+
+        let Some(lig) = (0..index).rev().find(|&j| {
+            let id = buffer.glyphs[j].glyph_id;
+            !self.is_mark(id) && !lookup.ignores(&self.gdef, id)
+        }) else { return false };
+
+        let ligature = buffer.glyphs[lig];
+        let Some(lig_index) = coverage_index(data, subtable + read_u16(data, subtable + 4) as usize, ligature.glyph_id as u16) else { return false };
+
+        let class_count = read_u16(data, subtable + 6);
+        let mark_array = subtable + read_u16(data, subtable + 8) as usize;
+        let lig_array = subtable + read_u16(data, subtable + 10) as usize;
+
+        if lig_index >= read_u16(data, lig_array) {
+            return false;
+        }
+
+        let lig_attach = lig_array + read_u16(data, lig_array + 2 + lig_index as usize * 2) as usize;
+        let component_count = read_u16(data, lig_attach);
+        if component_count == 0 {
+            return false;
+        }
+
+        // Marks that were between the ligature's components attach to that
+        // component; anything else goes on the last one
+        let component = if ligature.lig_id != 0 && mark.lig_id == ligature.lig_id && mark.lig_component > 0 {
+            (mark.lig_component as u16).min(component_count) - 1
+        } else {
+            component_count - 1
+        };
+
+        let Some((class, mark_anchor)) = self.mark_record(mark_array, mark_index) else { return false };
+        let Some(lig_anchor) = self.anchor_in_matrix(lig_attach, component, class, class_count) else { return false };
+
+        self.attach_mark(buffer, index, mark_anchor, lig, lig_anchor);
+        true
+    }
+
+    fn mark_to_mark(&self, lookup: &Lookup, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> bool {
+        let data = &self.gpos.data;
+        if read_u16(data, subtable) != 1 {
+            return false;
+        }
+
+        let mark1 = buffer.glyphs[index];
+        let Some(mark1_index) = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, mark1.glyph_id as u16) else { return false };
+
+        let Some(prev) = lookup.prev(&self.gdef, &buffer.glyphs, index) else { return false };
+        let mark2 = buffer.glyphs[prev];
+        if !self.is_mark(mark2.glyph_id) {
+            return false;
+        }
+
+        // Both marks must belong to the same base or ligature component,
+        // unless one of them was itself formed by a mark ligature
+        let same_component = if mark1.lig_id == mark2.lig_id {
+            mark1.lig_id == 0 || mark1.lig_component == mark2.lig_component
+        } else {
+            (mark1.lig_id > 0 && mark1.lig_component == 0) || (mark2.lig_id > 0 && mark2.lig_component == 0)
+        };
+
+        if !same_component {
+            return false;
+        }
+
+        let Some(mark2_index) = coverage_index(data, subtable + read_u16(data, subtable + 4) as usize, mark2.glyph_id as u16) else { return false };
+
+        let class_count = read_u16(data, subtable + 6);
+        let mark1_array = subtable + read_u16(data, subtable + 8) as usize;
+        let mark2_array = subtable + read_u16(data, subtable + 10) as usize;
+
+        let Some((class, mark1_anchor)) = self.mark_record(mark1_array, mark1_index) else { return false };
+        let Some(mark2_anchor) = self.anchor_in_matrix(mark2_array, mark2_index, class, class_count) else { return false };
+
+        self.attach_mark(buffer, index, mark1_anchor, prev, mark2_anchor);
+        true
+    }
+}
+
+/// Resolves the attachment of glyph `i` after its parent, turning the chain
+/// into offsets relative to the glyph's own pen position.
+fn resolve_attachment(positions: &mut [GlyphPosition], resolved: &mut [bool], i: usize, rtl: bool, depth: usize) {
+    if resolved[i] {
+        return;
+    }
+    resolved[i] = true;
+
+    let chain = positions[i].attach_chain;
+    if chain == 0 || depth >= MAX_NESTING_LEVEL {
+        return;
+    }
+
+    let Some(j) = i.checked_add_signed(chain as isize).filter(|&j| j < positions.len()) else { return };
+    resolve_attachment(positions, resolved, j, rtl, depth + 1);
+
+    let parent = positions[j];
+    positions[i].y_offset += parent.y_offset;
+
+    if positions[i].attach_type == ATTACH_MARK {
+        positions[i].x_offset += parent.x_offset;
+
+        // Move back to the parent's pen position
+        if j < i {
+            if rtl {
+                positions[i].x_offset += positions[j + 1..=i].iter().map(|p| p.x_advance).sum::<i32>();
+            } else {
+                positions[i].x_offset -= positions[j..i].iter().map(|p| p.x_advance).sum::<i32>();
+                positions[i].y_offset -= positions[j..i].iter().map(|p| p.y_advance).sum::<i32>();
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use crate::font::TrueTypeFont;
+use crate::shaping::{Feature, GlyphBuffer, GlyphInfo, Tag};
+use crate::shaping::buffer::GLOBAL_MASK;
+use crate::tables::common::{coverage_index, read_u16, ContextMatch, LayoutTable, Lookup, PlannedLookup};
+use crate::Vec;
+
+const SINGLE: u16 = 1;
+const MULTIPLE: u16 = 2;
+const ALTERNATE: u16 = 3;
+const LIGATURE: u16 = 4;
+const CONTEXT: u16 = 5;
+const CHAINED_CONTEXT: u16 = 6;
+const EXTENSION: u16 = 7;
+const REVERSE_CHAINED_CONTEXT: u16 = 8;
+
+/// Maximum depth of lookups applied from within contextual lookups.
+pub(crate) const MAX_NESTING_LEVEL: usize = 16;
+
+impl TrueTypeFont {
+    pub(crate) fn load_gsub(&mut self, font_bytes: &[u8]) {
+        if let Some(data) = self.table_data(b"GSUB", font_bytes) {
+            self.gsub = LayoutTable::parse(data);
+        }
+    }
+
+    /// Applies the GSUB lookups of `features` for the given script and
+    /// language system to every glyph in `buffer`.
+    pub fn apply_gsub(&self, buffer: &mut GlyphBuffer, script: Tag, language: Tag, features: &[Feature]) {
+        let features: Vec<(Feature, u32)> = features.iter().map(|&f| (f, GLOBAL_MASK)).collect();
+        let plan = self.gsub.plan(script, language, &features);
+        self.apply_gsub_plan(buffer, &plan);
+    }
+
+    /// Whether the font's GSUB table offers `feature` for the script and language.
+    pub fn has_gsub_feature(&self, script: Tag, language: Tag, feature: Tag) -> bool {
+        self.gsub.has_feature(script, language, feature)
+    }
+
+    pub(crate) fn apply_gsub_plan(&self, buffer: &mut GlyphBuffer, plan: &[PlannedLookup]) {
+        for planned in plan {
+            let Some(lookup) = self.gsub.lookup(planned.index, EXTENSION) else { continue };
+
+            if lookup.lookup_type == REVERSE_CHAINED_CONTEXT {
+                for i in (0..buffer.glyphs.len()).rev() {
+                    let info = buffer.glyphs[i];
+                    if info.mask & planned.mask == 0 || lookup.ignores(&self.gdef, info.glyph_id) {
+                        continue;
+                    }
+
+                    let substitute = lookup.subtables.iter()
+                        .find_map(|&subtable| self.gsub.match_reverse_chained(&lookup, &self.gdef, &buffer.glyphs, i, subtable));
+
+                    if let Some(substitute) = substitute {
+                        buffer.glyphs[i].glyph_id = substitute;
+                    }
+                }
+                continue;
+            }
+
+            let mut i = 0;
+            while i < buffer.glyphs.len() {
+                let info = buffer.glyphs[i];
+                if info.mask & planned.mask == 0 || lookup.ignores(&self.gdef, info.glyph_id) {
+                    i += 1;
+                    continue;
+                }
+
+                i = match self.substitute_at(&lookup, buffer, i, planned.value, 0) {
+                    Some(next) => next,
+                    None => i + 1,
+                };
+            }
+        }
+    }
+
+    /// Tries each subtable of `lookup` at `index`; on success returns the
+    /// position just past the glyphs the substitution produced.
+    pub(crate) fn substitute_at(&self, lookup: &Lookup, buffer: &mut GlyphBuffer, index: usize, value: u32, depth: usize) -> Option<usize> {
+        for &subtable in &lookup.subtables {
+            let applied = match lookup.lookup_type {
+                SINGLE => self.single_substitution(subtable, buffer, index),
+                MULTIPLE => self.multiple_substitution(subtable, buffer, index),
+                ALTERNATE => self.alternate_substitution(subtable, buffer, index, value),
+                LIGATURE => self.ligature_substitution(lookup, subtable, buffer, index),
+                CONTEXT => self.gsub.match_context(lookup, &self.gdef, &buffer.glyphs, index, subtable)
+                    .map(|m| self.apply_nested_substitutions(buffer, m, depth)),
+                CHAINED_CONTEXT => self.gsub.match_chained_context(lookup, &self.gdef, &buffer.glyphs, index, subtable)
+                    .map(|m| self.apply_nested_substitutions(buffer, m, depth)),
+                _ => None,
+            };
+
+            if applied.is_some() {
+                return applied;
+            }
+        }
+
+        None
+    }
+
+    /// Applies the sequence lookup records of a matched context, shifting the
+    /// remaining input positions as nested lookups grow or shrink the buffer.
+    fn apply_nested_substitutions(&self, buffer: &mut GlyphBuffer, matched: ContextMatch, depth: usize) -> usize {
+        let mut positions = matched.positions;
+        let mut end = positions[positions.len() - 1] + 1;
+
+        if depth >= MAX_NESTING_LEVEL {
+            return end;
+        }
+
+        for r in 0..matched.record_count {
+            let record = matched.records + r * 4;
+            let sequence_index = read_u16(&self.gsub.data, record) as usize;
+            let lookup_index = read_u16(&self.gsub.data, record + 2);
+
+            let Some(&pos) = positions.get(sequence_index) else { continue };
+            let Some(nested) = self.gsub.lookup(lookup_index, EXTENSION) else { continue };
+            if pos >= buffer.glyphs.len() {
+                continue;
+            }
+
+            let before = buffer.glyphs.len();
+            if self.substitute_at(&nested, buffer, pos, 1, depth + 1).is_none() {
+                continue;
+            }
+
+            let delta = buffer.glyphs.len() as isize - before as isize;
+            if delta != 0 {
+                for p in positions.iter_mut().filter(|p| **p > pos) {
+                    *p = p.saturating_add_signed(delta);
+                }
+                end = end.saturating_add_signed(delta).max(pos + 1);
+            }
+        }
+
+        end.min(buffer.glyphs.len())
+    }
+
+    fn single_substitution(&self, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> Option<usize> {
+        let data = &self.gsub.data;
+        let glyph = buffer.glyphs[index].glyph_id as u16;
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+
+        let substitute = match read_u16(data, subtable) {
+            1 => glyph.wrapping_add(read_u16(data, subtable + 4)),
+            2 => {
+                if coverage >= read_u16(data, subtable + 4) {
+                    return None;
+                }
+                read_u16(data, subtable + 6 + coverage as usize * 2)
+            }
+            _ => return None,
+        };
+
+        buffer.glyphs[index].glyph_id = substitute as u32;
+        Some(index + 1)
+    }
+
+    fn multiple_substitution(&self, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> Option<usize> {
+        let data = &self.gsub.data;
+        if read_u16(data, subtable) != 1 {
+            return None;
+        }
+
+        let glyph = buffer.glyphs[index].glyph_id as u16;
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+        if coverage >= read_u16(data, subtable + 4) {
+            return None;
+        }
+
+        let sequence = subtable + read_u16(data, subtable + 6 + coverage as usize * 2) as usize;
+        let count = read_u16(data, sequence) as usize;
+
+        let template = buffer.glyphs[index];
+        let replacement = (0..count).map(|i| GlyphInfo {
+            glyph_id: read_u16(data, sequence + 2 + i * 2) as u32,
+            ..template
+        });
+
+        buffer.glyphs.splice(index..=index, replacement);
+        Some(index + count)
+    }
+
+    fn alternate_substitution(&self, subtable: usize, buffer: &mut GlyphBuffer, index: usize, value: u32) -> Option<usize> {
+        let data = &self.gsub.data;
+        if read_u16(data, subtable) != 1 {
+            return None;
+        }
+
+        let glyph = buffer.glyphs[index].glyph_id as u16;
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+        if coverage >= read_u16(data, subtable + 4) {
+            return None;
+        }
+
+        let set = subtable + read_u16(data, subtable + 6 + coverage as usize * 2) as usize;
+        let count = read_u16(data, set) as u32;
+        if value == 0 || value > count {
+            return None;
+        }
+
+        buffer.glyphs[index].glyph_id = read_u16(data, set + 2 + (value as usize - 1) * 2) as u32;
+        Some(index + 1)
+    }
+
+    fn ligature_substitution(&self, lookup: &Lookup, subtable: usize, buffer: &mut GlyphBuffer, index: usize) -> Option<usize> {
+        let data = &self.gsub.data;
+        if read_u16(data, subtable) != 1 {
+            return None;
+        }
+
+        let glyph = buffer.glyphs[index].glyph_id as u16;
+        let coverage = coverage_index(data, subtable + read_u16(data, subtable + 2) as usize, glyph)?;
+        if coverage >= read_u16(data, subtable + 4) {
+            return None;
+        }
+
+        let set = subtable + read_u16(data, subtable + 6 + coverage as usize * 2) as usize;
+        let ligature_count = read_u16(data, set) as usize;
+
+        let mut positions = Vec::new();
+        for l in 0..ligature_count {
+            let ligature = set + read_u16(data, set + 2 + l * 2) as usize;
+            let component_count = read_u16(data, ligature + 2) as usize;
+            if component_count == 0 {
+                continue;
+            }
+
+            positions.clear();
+            positions.push(index);
+
+            let mut pos = index;
+            for c in 1..component_count {
+                match lookup.next(&self.gdef, &buffer.glyphs, pos) {
+                    Some(next) if buffer.glyphs[next].glyph_id as u16 == read_u16(data, ligature + 4 + (c - 1) * 2) => {
+                        positions.push(next);
+                        pos = next;
+                    }
+                    _ => break,
+                }
+            }
+
+            if positions.len() != component_count {
+                continue;
+            }
+
+            let lig_id = buffer.allocate_lig_id();
+            let cluster = positions.iter().map(|&p| buffer.glyphs[p].cluster).min().unwrap_or(0);
+            let last = *positions.last().unwrap();
+
+            // Marks skipped between components remember which component they follow
+            let mut component = 0;
+            for p in index..=last {
+                if positions.contains(&p) {
+                    component += 1;
+                } else {
+                    buffer.glyphs[p].lig_id = lig_id;
+                    buffer.glyphs[p].lig_component = component;
+                }
+            }
+
+            let head = &mut buffer.glyphs[index];
+            head.glyph_id = read_u16(data, ligature) as u32;
+            head.cluster = cluster;
+            head.lig_id = lig_id;
+            head.lig_component = 0;
+
+            for &p in positions[1..].iter().rev() {
+                buffer.glyphs.remove(p);
+            }
+
+            return Some(index + 1);
+        }
+
+        None
+    }
+}
@@ -63,8 +63,39 @@ impl TrueTypeFont {
     }
 
 
+    /// The glyph whose horizontal metrics `glyph_id` uses: a composite's
+    /// `USE_MY_METRICS` component, or the glyph itself.
+    pub(crate) fn metrics_glyph(&self, glyph_id: u32) -> u32 {
+        let mut id = glyph_id;
+
+        // Components nest only a few levels deep in real fonts
+        for _ in 0..8 {
+            match self.use_my_metrics(id) {
+                Some(component) if component != id => id = component,
+                _ => break,
+            }
+        }
+
+        id
+    }
+
+    /// Unscaled advance width and left side bearing of a glyph.
+    pub(crate) fn h_metric(&self, glyph_id: u32) -> (u16, i16) {
+        let idx = self.metrics_glyph(glyph_id) as usize;
+
+        if let Some(metric) = self.hmtx.h_metrics.get(idx) {
+            (metric.advance_width, metric.left_side_bearing)
+        } else {
+            let advance = self.hmtx.h_metrics.last().map(|m| m.advance_width).unwrap_or(0);
+            match self.hmtx.left_side_bearings.get(idx - self.hmtx.h_metrics.len()) {
+                Some(&lsb) => (advance, lsb),
+                None => (0, 0),
+            }
+        }
+    }
+
     pub(crate) fn get_metrics(&self, glyph_id: &u32, scale: f32) -> (usize, isize) {
-        let idx = *glyph_id as usize;
+        let idx = self.metrics_glyph(*glyph_id) as usize;
 
         if idx < self.hmtx.h_metrics.len() {
 
@@ -1,58 +1,297 @@
 use crate::font::{
     get_i16_be,
     get_u16_be,
+    get_u32_be,
     TrueTypeFont
 };
 
+use crate::Vec;
+
+// Microsoft (version 0) coverage bits
+const MS_HORIZONTAL: u16 = 0x0001;
+const MS_MINIMUM: u16 = 0x0002;
+const MS_CROSS_STREAM: u16 = 0x0004;
+const MS_OVERRIDE: u16 = 0x0008;
+
+// Apple (version 1.0) coverage bits
+const APPLE_VERTICAL: u16 = 0x8000;
+const APPLE_CROSS_STREAM: u16 = 0x4000;
+const APPLE_VARIATION: u16 = 0x2000;
+
+/// Every `i16`, indexed by its bits, so the deprecated lookups can keep
+/// returning references to values computed on the fly.
+static I16_VALUES: [i16; 1 << 16] = {
+    let mut values = [0; 1 << 16];
+    let mut i = 0;
+    while i < values.len() {
+        values[i] = i as u16 as i16;
+        i += 1;
+    }
+    values
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct KernTable {
+    pub(crate) data: Vec<u8>,
+    pub(crate) subtables: Vec<KernSubtable>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KernSubtable {
+    pub(crate) offset: usize,
+    pub(crate) length: usize,
+    pub(crate) header_size: usize,
+    pub(crate) format: u8,
+    pub(crate) horizontal: bool,
+    pub(crate) minimum: bool,
+    pub(crate) cross_stream: bool,
+    pub(crate) overrides: bool,
+}
+
+impl KernTable {
+    pub(crate) fn new() -> Self {
+        KernTable {
+            data: Vec::new(),
+            subtables: Vec::new(),
+        }
+    }
+
+    /// Returns the accumulated `(along, cross)` adjustment for a glyph pair,
+    /// or `None` when no horizontal subtable has an entry for it.
+    pub(crate) fn adjustment(&self, left: u16, right: u16) -> Option<(i16, i16)> {
+        let mut along = 0_i16;
+        let mut cross = 0_i16;
+        let mut found = false;
+
+        for subtable in &self.subtables {
+            if !subtable.horizontal {
+                continue;
+            }
+
+            let value = match subtable.format {
+                0 => self.format0_value(subtable, left, right),
+                2 => self.format2_value(subtable, left, right),
+                _ => None,
+            };
+
+            let Some(value) = value else { continue };
+            found = true;
+
+            if subtable.cross_stream {
+                // 0x8000 resets the cross-stream offset back to the baseline
+                cross = if value == i16::MIN {
+                    0
+                } else if subtable.overrides {
+                    value
+                } else {
+                    cross.saturating_add(value)
+                };
+            } else if subtable.minimum {
+                along = along.max(value);
+            } else if subtable.overrides {
+                along = value;
+            } else {
+                along = along.saturating_add(value);
+            }
+        }
+
+        if found { Some((along, cross)) } else { None }
+    }
+
+    fn format0_value(&self, subtable: &KernSubtable, left: u16, right: u16) -> Option<i16> {
+        let end = subtable.offset + subtable.length;
+        let base = subtable.offset + subtable.header_size;
+        if base + 8 > end {
+            return None;
+        }
+
+        // A bad nPairs must not reach into the next subtable
+        let n_pairs = get_u16_be(&self.data, base) as usize;
+        let pairs = base + 8;
+        let available = (end - pairs) / 6;
+        let key = ((left as u32) << 16) | right as u32;
+
+        let (mut lo, mut hi) = (0, n_pairs.min(available));
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let pair = pairs + mid * 6;
+            let candidate = get_u32_be(&self.data, pair);
+
+            if candidate == key {
+                return Some(get_i16_be(&self.data, pair + 4));
+            } else if candidate < key {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        None
+    }
+
+    fn format2_value(&self, subtable: &KernSubtable, left: u16, right: u16) -> Option<i16> {
+        let start = subtable.offset;
+        let end = start + subtable.length;
+        let base = start + subtable.header_size;
+        if base + 8 > end {
+            return None;
+        }
+
+        let left_table = get_u16_be(&self.data, base + 2) as usize;
+        let right_table = get_u16_be(&self.data, base + 4) as usize;
+        let array = get_u16_be(&self.data, base + 6) as usize;
+
+        // Glyphs outside a class table fall into class 0
+        let left_offset = self.class_value(start + left_table, end, left).unwrap_or(array);
+        let right_offset = self.class_value(start + right_table, end, right).unwrap_or(0);
+
+        let value_offset = start + left_offset + right_offset;
+        if value_offset + 2 > end {
+            return None;
+        }
+
+        match get_i16_be(&self.data, value_offset) {
+            0 => None,
+            value => Some(value),
+        }
+    }
+
+    fn class_value(&self, table: usize, end: usize, glyph: u16) -> Option<usize> {
+        if table + 4 > end {
+            return None;
+        }
+
+        let first_glyph = get_u16_be(&self.data, table);
+        let n_glyphs = get_u16_be(&self.data, table + 2);
+
+        if glyph < first_glyph || glyph - first_glyph >= n_glyphs {
+            return None;
+        }
+
+        let entry = table + 4 + (glyph - first_glyph) as usize * 2;
+        if entry + 2 > end {
+            return None;
+        }
+
+        Some(get_u16_be(&self.data, entry) as usize)
+    }
+}
+
 impl TrueTypeFont {
     pub(crate) fn load_kerning_pairs(&mut self, font_bytes: &[u8]) {
         for table in &self.tables {
             if table.table_tag == "kern".as_bytes() {
 
-                let offset = table.offset as usize;
-
-                let version = get_u16_be(font_bytes, offset);
-                let n_tables = get_u16_be(font_bytes, (offset + 2));
+                let start = table.offset as usize;
+                let end = (start + table.length as usize).min(font_bytes.len());
+                if start + 4 > end {
+                    return;
+                }
 
-                let mut subtable_offset = offset + 4;
+                let data = font_bytes[start..end].to_vec();
+                let mut subtables = Vec::new();
 
-                for _ in 0..n_tables {
-                    let version = get_u16_be(font_bytes, subtable_offset);
-                    let length = get_u16_be(font_bytes, (subtable_offset + 2));
-                    let coverage = get_u16_be(font_bytes, (subtable_offset + 4));
+                if get_u16_be(&data, 0) == 1 {
+                    // Apple 'kern' 1.0: 32-bit version and subtable count
+                    if data.len() < 8 {
+                        return;
+                    }
 
-                    let format = coverage >> 8;
-                    let horizontal = (coverage & 0x01) != 0;
+                    let n_tables = get_u32_be(&data, 4);
+                    let mut offset = 8;
 
-                    if format == 0 && horizontal {
-                        let n_pairs = get_u16_be(font_bytes, (subtable_offset + 6));
-                        let mut pair_offset = subtable_offset + 14;
+                    for _ in 0..n_tables {
+                        if offset + 8 > data.len() {
+                            break;
+                        }
 
-                        for _ in 0..n_pairs {
-                            let left = get_u16_be(font_bytes, pair_offset);
-                            let right = get_u16_be(font_bytes, (pair_offset + 2));
-                            let value = get_i16_be(font_bytes, (pair_offset + 4));
+                        let length = get_u32_be(&data, offset) as usize;
+                        let coverage = get_u16_be(&data, offset + 4);
+                        let length = length.clamp(8, data.len() - offset);
 
-                            self.kern_table.insert((left as u32, right as u32), value);
-                            pair_offset += 6;
+                        if coverage & APPLE_VARIATION == 0 {
+                            subtables.push(KernSubtable {
+                                offset,
+                                length,
+                                header_size: 8,
+                                format: (coverage & 0xFF) as u8,
+                                horizontal: coverage & APPLE_VERTICAL == 0,
+                                minimum: false,
+                                cross_stream: coverage & APPLE_CROSS_STREAM != 0,
+                                overrides: false,
+                            });
                         }
+
+                        offset += length;
                     }
+                } else {
+                    let n_tables = get_u16_be(&data, 2);
+                    let mut offset = 4;
+
+                    for _ in 0..n_tables {
+                        if offset + 6 > data.len() {
+                            break;
+                        }
+
+                        let coverage = get_u16_be(&data, offset + 4);
+                        let format = (coverage >> 8) as u8;
+
+                        // The 16-bit length overflows for large format 0 subtables,
+                        // so derive it from the pair count when the stored length
+                        // is that count's truncation
+                        let stored_length = get_u16_be(&data, offset + 2) as usize;
+                        let pairs_length = if format == 0 && offset + 8 <= data.len() {
+                            14 + get_u16_be(&data, offset + 6) as usize * 6
+                        } else {
+                            stored_length
+                        };
+                        let length = if pairs_length & 0xFFFF == stored_length { pairs_length } else { stored_length };
+                        let length = length.clamp(6, data.len() - offset);
 
-                    subtable_offset += length as usize;
+                        subtables.push(KernSubtable {
+                            offset,
+                            length,
+                            header_size: 6,
+                            format,
+                            horizontal: coverage & MS_HORIZONTAL != 0,
+                            minimum: coverage & MS_MINIMUM != 0,
+                            cross_stream: coverage & MS_CROSS_STREAM != 0,
+                            overrides: coverage & MS_OVERRIDE != 0,
+                        });
+
+                        offset += length;
+                    }
                 }
 
+                self.kern = KernTable { data, subtables };
                 return;
             }
         }
     }
 
+    /// Horizontal kerning between two glyphs, in font units, combining every
+    /// horizontal subtable by its coverage flags.
+    pub fn kerning_by_id(&self, left: u32, right: u32) -> Option<i16> {
+        self.kern.adjustment(left as u16, right as u16).map(|(along, _)| along)
+    }
+
+    /// Cross-stream (vertical) shift between two glyphs, in font units.
+    pub fn cross_stream_kerning_by_id(&self, left: u32, right: u32) -> Option<i16> {
+        self.kern.adjustment(left as u16, right as u16).map(|(_, cross)| cross)
+    }
+
+    /// Horizontal kerning between two characters, in font units.
+    pub fn kerning(&self, left: char, right: char) -> Option<i16> {
+        self.kerning_by_id(self.get_glyph_id(left), self.get_glyph_id(right))
+    }
+
+    #[deprecated(note = "use `kerning_by_id`")]
     pub fn get_kerning_by_id(&self, left: u32, right: u32) -> Option<&i16> {
-        self.kern_table.get(&(left, right))
+        self.kerning_by_id(left, right).map(|value| &I16_VALUES[value as u16 as usize])
     }
 
+    #[deprecated(note = "use `kerning`")]
     pub fn get_kerning(&self, left: char, right: char) -> Option<&i16> {
-        let left = self.glyph_id_table.get(&left).unwrap_or(&0);
-        let right = self.glyph_id_table.get(&right).unwrap_or(&0);
-        self.kern_table.get(&(*left, *right))
+        self.kerning(left, right).map(|value| &I16_VALUES[value as u16 as usize])
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,18 @@
 pub(crate) mod cmap;
+pub(crate) mod common;
+pub(crate) mod gdef;
 pub(crate) mod glyf;
+pub(crate) mod gpos;
+pub(crate) mod gsub;
 pub(crate) mod head;
 pub(crate) mod hhea;
 pub(crate) mod hmtx;
 pub(crate) mod loca;
 pub(crate) mod maxp;
+pub mod name;
+pub mod os2;
+pub mod post;
+pub mod vhea;
+pub mod vmtx;
+pub(crate) mod vorg;
 pub(crate) mod kern;
\ No newline at end of file
@@ -0,0 +1,256 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::read_u16;
+use crate::{String, Vec};
+
+pub const COPYRIGHT: u16 = 0;
+pub const FAMILY: u16 = 1;
+pub const SUBFAMILY: u16 = 2;
+pub const UNIQUE_ID: u16 = 3;
+pub const FULL_NAME: u16 = 4;
+pub const VERSION: u16 = 5;
+pub const POSTSCRIPT_NAME: u16 = 6;
+pub const TRADEMARK: u16 = 7;
+pub const MANUFACTURER: u16 = 8;
+pub const DESIGNER: u16 = 9;
+pub const DESCRIPTION: u16 = 10;
+pub const VENDOR_URL: u16 = 11;
+pub const DESIGNER_URL: u16 = 12;
+pub const LICENSE: u16 = 13;
+pub const LICENSE_URL: u16 = 14;
+pub const TYPOGRAPHIC_FAMILY: u16 = 16;
+pub const TYPOGRAPHIC_SUBFAMILY: u16 = 17;
+
+const PLATFORM_UNICODE: u16 = 0;
+const PLATFORM_MACINTOSH: u16 = 1;
+const PLATFORM_WINDOWS: u16 = 3;
+
+/// One decoded string of the `name` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameRecord {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    pub language_id: u16,
+    pub name_id: u16,
+    /// BCP 47 language tag, from the format 1 language tag records or the
+    /// platform language id. `None` for languages not known here.
+    pub language: Option<String>,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NameTable {
+    /// Records in table order, skipping those in encodings that cannot be
+    /// decoded.
+    pub records: Vec<NameRecord>,
+}
+
+impl NameTable {
+    pub(crate) fn new() -> Self {
+        NameTable { records: Vec::new() }
+    }
+
+    /// The string `name_id` in the preferred `language`, such as `"de"` or
+    /// `"pt-BR"`. Falls back to the same language in another region, then to
+    /// English, then to any record with that id.
+    pub fn get(&self, name_id: u16, language: &str) -> Option<&str> {
+        let primary = |tag: &str| tag.split('-').next().unwrap_or("").to_ascii_lowercase();
+        let wanted = primary(language);
+
+        let score = |record: &NameRecord| {
+            let tag = record.language.as_deref().unwrap_or("");
+            let language_score = if tag.eq_ignore_ascii_case(language) {
+                3
+            } else if !tag.is_empty() && primary(tag) == wanted {
+                2
+            } else if primary(tag) == "en" {
+                1
+            } else {
+                0
+            };
+
+            // Prefer Windows, then Unicode, then Macintosh strings
+            let platform_score = match record.platform_id {
+                PLATFORM_WINDOWS => 2,
+                PLATFORM_UNICODE => 1,
+                _ => 0,
+            };
+
+            language_score * 4 + platform_score
+        };
+
+        let mut best: Option<(&NameRecord, u32)> = None;
+        for record in self.records.iter().filter(|record| record.name_id == name_id) {
+            let record_score = score(record);
+            if best.is_none_or(|(_, best_score)| record_score > best_score) {
+                best = Some((record, record_score));
+            }
+        }
+
+        best.map(|(record, _)| record.value.as_str())
+    }
+
+    pub fn family(&self, language: &str) -> Option<&str> {
+        self.get(FAMILY, language)
+    }
+
+    pub fn subfamily(&self, language: &str) -> Option<&str> {
+        self.get(SUBFAMILY, language)
+    }
+
+    pub fn full_name(&self, language: &str) -> Option<&str> {
+        self.get(FULL_NAME, language)
+    }
+
+    pub fn postscript_name(&self) -> Option<&str> {
+        self.get(POSTSCRIPT_NAME, "en")
+    }
+
+    /// The typographic family (id 16), or the family (id 1) for fonts whose
+    /// families fit the four style linking styles.
+    pub fn typographic_family(&self, language: &str) -> Option<&str> {
+        self.get(TYPOGRAPHIC_FAMILY, language).or_else(|| self.family(language))
+    }
+
+    /// The typographic subfamily (id 17), or the subfamily (id 2).
+    pub fn typographic_subfamily(&self, language: &str) -> Option<&str> {
+        self.get(TYPOGRAPHIC_SUBFAMILY, language).or_else(|| self.subfamily(language))
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.get(VERSION, "en")
+    }
+
+    pub fn copyright(&self, language: &str) -> Option<&str> {
+        self.get(COPYRIGHT, language)
+    }
+
+    pub fn license(&self, language: &str) -> Option<&str> {
+        self.get(LICENSE, language)
+    }
+}
+
+/// Characters 0x80 to 0xFF of the Mac OS Roman encoding.
+pub(crate) const MAC_ROMAN: [char; 128] = [
+    '\u{00C4}', '\u{00C5}', '\u{00C7}', '\u{00C9}', '\u{00D1}', '\u{00D6}', '\u{00DC}', '\u{00E1}',
+    '\u{00E0}', '\u{00E2}', '\u{00E4}', '\u{00E3}', '\u{00E5}', '\u{00E7}', '\u{00E9}', '\u{00E8}',
+    '\u{00EA}', '\u{00EB}', '\u{00ED}', '\u{00EC}', '\u{00EE}', '\u{00EF}', '\u{00F1}', '\u{00F3}',
+    '\u{00F2}', '\u{00F4}', '\u{00F6}', '\u{00F5}', '\u{00FA}', '\u{00F9}', '\u{00FB}', '\u{00FC}',
+    '\u{2020}', '\u{00B0}', '\u{00A2}', '\u{00A3}', '\u{00A7}', '\u{2022}', '\u{00B6}', '\u{00DF}',
+    '\u{00AE}', '\u{00A9}', '\u{2122}', '\u{00B4}', '\u{00A8}', '\u{2260}', '\u{00C6}', '\u{00D8}',
+    '\u{221E}', '\u{00B1}', '\u{2264}', '\u{2265}', '\u{00A5}', '\u{00B5}', '\u{2202}', '\u{2211}',
+    '\u{220F}', '\u{03C0}', '\u{222B}', '\u{00AA}', '\u{00BA}', '\u{03A9}', '\u{00E6}', '\u{00F8}',
+    '\u{00BF}', '\u{00A1}', '\u{00AC}', '\u{221A}', '\u{0192}', '\u{2248}', '\u{2206}', '\u{00AB}',
+    '\u{00BB}', '\u{2026}', '\u{00A0}', '\u{00C0}', '\u{00C3}', '\u{00D5}', '\u{0152}', '\u{0153}',
+    '\u{2013}', '\u{2014}', '\u{201C}', '\u{201D}', '\u{2018}', '\u{2019}', '\u{00F7}', '\u{25CA}',
+    '\u{00FF}', '\u{0178}', '\u{2044}', '\u{20AC}', '\u{2039}', '\u{203A}', '\u{FB01}', '\u{FB02}',
+    '\u{2021}', '\u{00B7}', '\u{201A}', '\u{201E}', '\u{2030}', '\u{00C2}', '\u{00CA}', '\u{00C1}',
+    '\u{00CB}', '\u{00C8}', '\u{00CD}', '\u{00CE}', '\u{00CF}', '\u{00CC}', '\u{00D3}', '\u{00D4}',
+    '\u{F8FF}', '\u{00D2}', '\u{00DA}', '\u{00DB}', '\u{00D9}', '\u{0131}', '\u{02C6}', '\u{02DC}',
+    '\u{00AF}', '\u{02D8}', '\u{02D9}', '\u{02DA}', '\u{00B8}', '\u{02DD}', '\u{02DB}', '\u{02C7}',
+];
+
+pub(crate) fn mac_roman_char(byte: u8) -> char {
+    if byte < 0x80 { byte as char } else { MAC_ROMAN[byte as usize - 0x80] }
+}
+
+/// The Mac OS Roman byte for `c`, if the encoding has it.
+pub(crate) fn mac_roman_byte(c: char) -> Option<u8> {
+    if (c as u32) < 0x80 {
+        return Some(c as u8);
+    }
+    MAC_ROMAN.iter().position(|&m| m == c).map(|i| i as u8 + 0x80)
+}
+
+fn decode_utf16_be(bytes: &[u8]) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+}
+
+/// Language tags of the most common Windows LCIDs, by primary language.
+const WINDOWS_LANGUAGES: [(u16, &str); 45] = [
+    (0x01, "ar"), (0x02, "bg"), (0x03, "ca"), (0x04, "zh"), (0x05, "cs"), (0x06, "da"), (0x07, "de"),
+    (0x08, "el"), (0x09, "en"), (0x0A, "es"), (0x0B, "fi"), (0x0C, "fr"), (0x0D, "he"), (0x0E, "hu"),
+    (0x0F, "is"), (0x10, "it"), (0x11, "ja"), (0x12, "ko"), (0x13, "nl"), (0x14, "no"), (0x15, "pl"),
+    (0x16, "pt"), (0x18, "ro"), (0x19, "ru"), (0x1A, "hr"), (0x1B, "sk"), (0x1C, "sq"), (0x1D, "sv"),
+    (0x1E, "th"), (0x1F, "tr"), (0x20, "ur"), (0x21, "id"), (0x22, "uk"), (0x23, "be"), (0x24, "sl"),
+    (0x25, "et"), (0x26, "lv"), (0x27, "lt"), (0x29, "fa"), (0x2A, "vi"), (0x2D, "eu"), (0x39, "hi"),
+    (0x3E, "ms"), (0x45, "bn"), (0x49, "ta"),
+];
+
+/// Windows LCIDs whose region matters for picking a name.
+const WINDOWS_REGIONS: [(u16, &str); 8] = [
+    (0x0404, "zh-TW"), (0x0804, "zh-CN"), (0x0C04, "zh-HK"), (0x1004, "zh-SG"),
+    (0x0409, "en-US"), (0x0809, "en-GB"), (0x0416, "pt-BR"), (0x0816, "pt-PT"),
+];
+
+/// Language tags of the Macintosh language ids, by id.
+const MACINTOSH_LANGUAGES: [&str; 34] = [
+    "en", "fr", "de", "it", "nl", "sv", "es", "da", "pt", "no", "he", "ja", "ar", "fi", "el", "is", "mt",
+    "tr", "hr", "zh-Hant", "ur", "hi", "th", "ko", "lt", "pl", "hu", "et", "lv", "se", "fo", "fa", "ru",
+    "zh-Hans",
+];
+
+fn platform_language(platform_id: u16, language_id: u16) -> Option<&'static str> {
+    match platform_id {
+        PLATFORM_WINDOWS => WINDOWS_REGIONS.iter()
+            .find(|&&(lcid, _)| lcid == language_id)
+            .or_else(|| WINDOWS_LANGUAGES.iter().find(|&&(primary, _)| primary == language_id & 0x3FF))
+            .map(|&(_, tag)| tag),
+        PLATFORM_MACINTOSH => MACINTOSH_LANGUAGES.get(language_id as usize).copied(),
+        _ => None,
+    }
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_name(&mut self, font_bytes: &[u8]) {
+        let Some(data) = self.table_data(b"name", font_bytes) else { return };
+
+        let version = read_u16(&data, 0);
+        let count = read_u16(&data, 2) as usize;
+        let storage = read_u16(&data, 4) as usize;
+
+        let string = |offset: usize, length: usize| data.get(storage + offset..storage + offset + length);
+
+        // Format 1 appends language tags, referenced by language ids from 0x8000
+        let mut language_tags = Vec::new();
+        if version == 1 {
+            let tags_start = 6 + count * 12;
+            for i in 0..read_u16(&data, tags_start) as usize {
+                let record = tags_start + 2 + i * 4;
+                let tag = string(read_u16(&data, record + 2) as usize, read_u16(&data, record) as usize);
+                language_tags.push(tag.map(decode_utf16_be));
+            }
+        }
+
+        for i in 0..count {
+            let record = 6 + i * 12;
+            let platform_id = read_u16(&data, record);
+            let encoding_id = read_u16(&data, record + 2);
+            let language_id = read_u16(&data, record + 4);
+            let name_id = read_u16(&data, record + 6);
+            let length = read_u16(&data, record + 8) as usize;
+            let offset = read_u16(&data, record + 10) as usize;
+
+            let Some(bytes) = string(offset, length) else { continue };
+
+            let value = match (platform_id, encoding_id) {
+                (PLATFORM_UNICODE, _) | (PLATFORM_WINDOWS, 0 | 1 | 10) => decode_utf16_be(bytes),
+                (PLATFORM_MACINTOSH, 0) => bytes.iter().map(|&b| mac_roman_char(b)).collect(),
+                _ => continue,
+            };
+
+            let language = if language_id >= 0x8000 {
+                language_tags.get(language_id as usize - 0x8000).cloned().flatten()
+            } else {
+                platform_language(platform_id, language_id).map(String::from)
+            };
+
+            self.name.records.push(NameRecord { platform_id, encoding_id, language_id, name_id, language, value });
+        }
+    }
+
+    /// The decoded `name` table: family, style, version and legal strings.
+    pub fn names(&self) -> &NameTable {
+        &self.name
+    }
+}
@@ -0,0 +1,195 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::{read_i16, read_tag, read_u16, read_u32};
+
+pub const ITALIC: u16 = 1 << 0;
+pub const UNDERSCORE: u16 = 1 << 1;
+pub const NEGATIVE: u16 = 1 << 2;
+pub const OUTLINED: u16 = 1 << 3;
+pub const STRIKEOUT: u16 = 1 << 4;
+pub const BOLD: u16 = 1 << 5;
+pub const REGULAR: u16 = 1 << 6;
+/// `fsSelection` bit asking for the typographic metrics to set line spacing.
+pub const USE_TYPO_METRICS: u16 = 1 << 7;
+pub const WWS: u16 = 1 << 8;
+pub const OBLIQUE: u16 = 1 << 9;
+
+/// Size and offset of synthesized subscripts or superscripts, in font units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScriptMetrics {
+    pub x_size: i16,
+    pub y_size: i16,
+    pub x_offset: i16,
+    pub y_offset: i16,
+}
+
+/// How a font may be embedded in documents, from the `fsType` usage bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingPermissions {
+    /// May be embedded and permanently installed.
+    Installable,
+    /// Must not be embedded without the owner's permission.
+    Restricted,
+    /// May be embedded to view and print documents only.
+    PreviewAndPrint,
+    /// May be embedded in documents that are edited.
+    Editable,
+}
+
+/// The OS/2 and Windows metrics table. Fields added after version 0 are
+/// `None` when the table is too old to have them, and so are the vertical
+/// metrics of early Apple tables, which stop after `usLastCharIndex`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Os2Table {
+    pub version: u16,
+    pub x_avg_char_width: i16,
+    /// 100 (thin) to 900 (black); 400 is regular and 700 bold.
+    pub weight_class: u16,
+    /// 1 (ultra condensed) to 9 (ultra expanded); 5 is normal.
+    pub width_class: u16,
+    pub fs_type: u16,
+    pub subscript: ScriptMetrics,
+    pub superscript: ScriptMetrics,
+    pub strikeout_size: i16,
+    pub strikeout_position: i16,
+    pub family_class: i16,
+    pub panose: [u8; 10],
+    /// Unicode blocks the font claims to support, bit 0 of the first word
+    /// being Basic Latin.
+    pub unicode_range: [u32; 4],
+    pub vendor_id: [u8; 4],
+    pub fs_selection: u16,
+    pub first_char_index: u16,
+    pub last_char_index: u16,
+    pub typo_ascender: Option<i16>,
+    pub typo_descender: Option<i16>,
+    pub typo_line_gap: Option<i16>,
+    pub win_ascent: Option<u16>,
+    pub win_descent: Option<u16>,
+    /// Version 1 and later.
+    pub code_page_range: Option<[u32; 2]>,
+    /// Version 2 and later.
+    pub x_height: Option<i16>,
+    /// Version 2 and later.
+    pub cap_height: Option<i16>,
+    /// Version 2 and later.
+    pub default_char: Option<u16>,
+    /// Version 2 and later.
+    pub break_char: Option<u16>,
+    /// Version 2 and later.
+    pub max_context: Option<u16>,
+    /// Version 5 only, in TWIPs (twentieths of a point).
+    pub lower_optical_point_size: Option<u16>,
+    /// Version 5 only, in TWIPs (twentieths of a point).
+    pub upper_optical_point_size: Option<u16>,
+}
+
+impl Os2Table {
+    pub fn is_italic(&self) -> bool {
+        self.fs_selection & ITALIC != 0
+    }
+
+    pub fn is_bold(&self) -> bool {
+        self.fs_selection & BOLD != 0
+    }
+
+    pub fn is_regular(&self) -> bool {
+        self.fs_selection & REGULAR != 0
+    }
+
+    pub fn is_oblique(&self) -> bool {
+        self.version >= 4 && self.fs_selection & OBLIQUE != 0
+    }
+
+    pub fn use_typo_metrics(&self) -> bool {
+        self.version >= 4 && self.fs_selection & USE_TYPO_METRICS != 0
+    }
+
+    /// Whether the font claims support for Unicode range `bit` (0 to 127).
+    pub fn has_unicode_range(&self, bit: u32) -> bool {
+        bit < 128 && self.unicode_range[(bit / 32) as usize] & (1 << (bit % 32)) != 0
+    }
+
+    /// The embedding level. Several usage bits set at once are read as the
+    /// least restrictive of them, as the specification asks.
+    pub fn embedding_permissions(&self) -> EmbeddingPermissions {
+        if self.fs_type & 0x8 != 0 {
+            EmbeddingPermissions::Editable
+        } else if self.fs_type & 0x4 != 0 {
+            EmbeddingPermissions::PreviewAndPrint
+        } else if self.fs_type & 0x2 != 0 {
+            EmbeddingPermissions::Restricted
+        } else {
+            EmbeddingPermissions::Installable
+        }
+    }
+
+    /// Whether the font must be embedded whole rather than subset.
+    pub fn no_subsetting(&self) -> bool {
+        self.version >= 2 && self.fs_type & 0x100 != 0
+    }
+
+    /// Whether only bitmaps, not outlines, may be embedded.
+    pub fn bitmap_embedding_only(&self) -> bool {
+        self.version >= 2 && self.fs_type & 0x200 != 0
+    }
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_os2(&mut self, font_bytes: &[u8]) {
+        let Some(data) = self.table_data(b"OS/2", font_bytes) else { return };
+
+        // Early Apple tables stop after usLastCharIndex
+        if data.len() < 68 {
+            return;
+        }
+
+        let version = read_u16(&data, 0);
+        let since = |min_version: u16, end: usize| version >= min_version && data.len() >= end;
+        let script_metrics = |offset: usize| ScriptMetrics {
+            x_size: read_i16(&data, offset),
+            y_size: read_i16(&data, offset + 2),
+            x_offset: read_i16(&data, offset + 4),
+            y_offset: read_i16(&data, offset + 6),
+        };
+
+        let mut panose = [0; 10];
+        panose.copy_from_slice(&data[32..42]);
+
+        self.os2 = Some(Os2Table {
+            version,
+            x_avg_char_width: read_i16(&data, 2),
+            weight_class: read_u16(&data, 4),
+            width_class: read_u16(&data, 6),
+            fs_type: read_u16(&data, 8),
+            subscript: script_metrics(10),
+            superscript: script_metrics(18),
+            strikeout_size: read_i16(&data, 26),
+            strikeout_position: read_i16(&data, 28),
+            family_class: read_i16(&data, 30),
+            panose,
+            unicode_range: [read_u32(&data, 42), read_u32(&data, 46), read_u32(&data, 50), read_u32(&data, 54)],
+            vendor_id: read_tag(&data, 58),
+            fs_selection: read_u16(&data, 62),
+            first_char_index: read_u16(&data, 64),
+            last_char_index: read_u16(&data, 66),
+            typo_ascender: since(0, 78).then(|| read_i16(&data, 68)),
+            typo_descender: since(0, 78).then(|| read_i16(&data, 70)),
+            typo_line_gap: since(0, 78).then(|| read_i16(&data, 72)),
+            win_ascent: since(0, 78).then(|| read_u16(&data, 74)),
+            win_descent: since(0, 78).then(|| read_u16(&data, 76)),
+            code_page_range: since(1, 86).then(|| [read_u32(&data, 78), read_u32(&data, 82)]),
+            x_height: since(2, 96).then(|| read_i16(&data, 86)),
+            cap_height: since(2, 96).then(|| read_i16(&data, 88)),
+            default_char: since(2, 96).then(|| read_u16(&data, 90)),
+            break_char: since(2, 96).then(|| read_u16(&data, 92)),
+            max_context: since(2, 96).then(|| read_u16(&data, 94)),
+            lower_optical_point_size: since(5, 100).then(|| read_u16(&data, 96)),
+            upper_optical_point_size: since(5, 100).then(|| read_u16(&data, 98)),
+        });
+    }
+
+    /// The OS/2 table, if the font has one.
+    pub fn os2(&self) -> Option<&Os2Table> {
+        self.os2.as_ref()
+    }
+}
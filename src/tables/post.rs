@@ -0,0 +1,139 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::{read_i16, read_u16, read_u32};
+use crate::{String, Vec};
+
+/// The post table. Glyph names come from the standard Macintosh glyph order
+/// in version 1, from that order plus Pascal strings in version 2, and are
+/// absent in version 3.
+#[derive(Debug, Clone)]
+pub struct PostTable {
+    /// 16.16 fixed point version: 0x00010000, 0x00020000, 0x00025000 or 0x00030000.
+    pub version: u32,
+    /// Degrees counter-clockwise from vertical; negative for fonts leaning right.
+    pub italic_angle: f32,
+    pub underline_position: i16,
+    pub underline_thickness: i16,
+    /// Whether every glyph has the same advance width.
+    pub is_fixed_pitch: bool,
+    /// Per glyph index into the standard names, or from 258 on into `names`.
+    name_indices: Vec<u16>,
+    names: Vec<String>,
+}
+
+impl PostTable {
+    /// The PostScript name of a glyph, if the table records one.
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<&str> {
+        let index = match self.version {
+            0x0001_0000 => glyph_id as usize,
+            _ => *self.name_indices.get(glyph_id as usize)? as usize,
+        };
+
+        match index {
+            0..258 => MACINTOSH_NAMES.get(index).copied(),
+            _ => self.names.get(index - 258).map(String::as_str),
+        }
+    }
+
+    /// The first glyph called `name`.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
+        let count = match self.version {
+            0x0001_0000 => MACINTOSH_NAMES.len(),
+            _ => self.name_indices.len(),
+        };
+        (0..count as u32).find(|&glyph_id| self.glyph_name(glyph_id) == Some(name))
+    }
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_post(&mut self, font_bytes: &[u8]) {
+        let Some(data) = self.table_data(b"post", font_bytes) else { return };
+        if data.len() < 32 {
+            return;
+        }
+
+        let version = read_u32(&data, 0);
+        let mut name_indices = Vec::new();
+        let mut names = Vec::new();
+
+        match version {
+            0x0002_0000 => {
+                let count = read_u16(&data, 32) as usize;
+                name_indices.extend((0..count).map(|i| read_u16(&data, 34 + i * 2)));
+
+                let mut offset = 34 + count * 2;
+                while let Some(&length) = data.get(offset) {
+                    let Some(bytes) = data.get(offset + 1..offset + 1 + length as usize) else { break };
+                    names.push(bytes.iter().map(|&b| b as char).collect());
+                    offset += 1 + length as usize;
+                }
+            }
+            0x0002_5000 => {
+                // Deprecated: each glyph's name is the standard name at an offset
+                let count = read_u16(&data, 32) as usize;
+                name_indices.extend((0..count).filter_map(|i| {
+                    let delta = *data.get(34 + i)? as i8;
+                    Some((i as i32 + delta as i32).clamp(0, u16::MAX as i32) as u16)
+                }));
+            }
+            _ => {}
+        }
+
+        self.post = Some(PostTable {
+            version,
+            italic_angle: read_u32(&data, 4) as i32 as f32 / 65536.0,
+            underline_position: read_i16(&data, 8),
+            underline_thickness: read_i16(&data, 10),
+            is_fixed_pitch: read_u32(&data, 12) != 0,
+            name_indices,
+            names,
+        });
+    }
+
+    /// The post table, if the font has one.
+    pub fn post(&self) -> Option<&PostTable> {
+        self.post.as_ref()
+    }
+
+    /// The PostScript name of a glyph, such as `"Aacute"` or `"uni0416"`.
+    pub fn glyph_name(&self, glyph_id: u32) -> Option<&str> {
+        self.post.as_ref()?.glyph_name(glyph_id)
+    }
+
+    /// The glyph with PostScript name `name`.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u32> {
+        self.post.as_ref()?.glyph_by_name(name)
+    }
+}
+
+/// The 258 glyph names of the standard Macintosh glyph order.
+const MACINTOSH_NAMES: [&str; 258] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+    "percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+    "hyphen", "period", "slash", "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B",
+    "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum",
+    "underscore", "grave", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright",
+    "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis",
+    "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute",
+    "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde",
+    "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex",
+    "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph",
+    "germandbls", "registered", "copyright", "trademark", "acute", "dieresis", "notequal", "AE",
+    "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu", "partialdiff",
+    "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega", "ae",
+    "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal",
+    "Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace", "Agrave", "Atilde",
+    "Otilde", "OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency",
+    "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl", "periodcentered", "quotesinglbase",
+    "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute", "Edieresis", "Egrave",
+    "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex", "apple", "Ograve",
+    "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde", "macron", "breve",
+    "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron", "Lslash", "lslash", "Scaron",
+    "scaron", "Zcaron", "zcaron", "brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn", "thorn",
+    "minus", "multiply", "onesuperior", "twosuperior", "threesuperior", "onehalf", "onequarter",
+    "threequarters", "franc", "Gbreve", "gbreve", "Idotaccent", "Scedilla", "scedilla", "Cacute",
+    "cacute", "Ccaron", "ccaron", "dcroat",
+];
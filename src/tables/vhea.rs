@@ -0,0 +1,50 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::{read_i16, read_u16, read_u32};
+
+/// The vertical header table, the vertical counterpart of `hhea`. Ascender
+/// and descender are measured across the column from its center line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VheaTable {
+    /// 16.16 fixed point version: 0x00010000 or 0x00011000.
+    pub version: u32,
+    pub ascender: i16,
+    pub descender: i16,
+    pub line_gap: i16,
+    pub advance_height_max: i16,
+    pub min_top_side_bearing: i16,
+    pub min_bottom_side_bearing: i16,
+    pub y_max_extent: i16,
+    pub caret_slope_rise: i16,
+    pub caret_slope_run: i16,
+    pub caret_offset: i16,
+    pub number_of_long_ver_metrics: u16,
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_vhea(&mut self, font_bytes: &[u8]) {
+        let Some(data) = self.table_data(b"vhea", font_bytes) else { return };
+        if data.len() < 36 {
+            return;
+        }
+
+        self.vhea = Some(VheaTable {
+            version: read_u32(&data, 0),
+            ascender: read_i16(&data, 4),
+            descender: read_i16(&data, 6),
+            line_gap: read_i16(&data, 8),
+            advance_height_max: read_i16(&data, 10),
+            min_top_side_bearing: read_i16(&data, 12),
+            min_bottom_side_bearing: read_i16(&data, 14),
+            y_max_extent: read_i16(&data, 16),
+            caret_slope_rise: read_i16(&data, 18),
+            caret_slope_run: read_i16(&data, 20),
+            caret_offset: read_i16(&data, 22),
+            number_of_long_ver_metrics: read_u16(&data, 34),
+        });
+    }
+
+    /// The vertical header table, if the font has one.
+    pub fn vhea(&self) -> Option<&VheaTable> {
+        self.vhea.as_ref()
+    }
+}
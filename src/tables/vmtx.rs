@@ -0,0 +1,96 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::{read_i16, read_u16};
+use crate::Vec;
+
+#[derive(Debug, Clone)]
+pub(crate) struct VmtxTable {
+    pub(crate) v_metrics: Vec<LongVerMetric>,
+    pub(crate) top_side_bearings: Vec<i16>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LongVerMetric {
+    pub(crate) advance_height: u16,
+    pub(crate) top_side_bearing: i16,
+}
+
+/// Metrics of a glyph set in a vertical line, in font units. Fonts without
+/// `vhea` and `vmtx` get metrics synthesized from `hhea`: every glyph
+/// advances by the ascender to descender distance, with its origin at the
+/// ascender.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerticalMetrics {
+    pub advance_height: u16,
+    /// Distance from the vertical origin down to the top of the outline.
+    pub top_side_bearing: i16,
+    /// The vertical origin, where the pen sits in vertical text, relative
+    /// to the horizontal origin: half the advance width across and the top
+    /// of the glyph's em box up.
+    pub origin_x: i16,
+    pub origin_y: i16,
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_vmtx(&mut self, font_bytes: &[u8]) {
+        let Some(vhea) = self.vhea else { return };
+        let Some(data) = self.table_data(b"vmtx", font_bytes) else { return };
+
+        let long_metrics = (vhea.number_of_long_ver_metrics as usize).min(data.len() / 4);
+        let v_metrics = (0..long_metrics)
+            .map(|i| LongVerMetric {
+                advance_height: read_u16(&data, i * 4),
+                top_side_bearing: read_i16(&data, i * 4 + 2),
+            })
+            .collect();
+
+        let remaining = (self.maxp.num_glyphs as usize).saturating_sub(long_metrics);
+        let top_side_bearings = (0..remaining)
+            .take_while(|i| long_metrics * 4 + i * 2 + 2 <= data.len())
+            .map(|i| read_i16(&data, long_metrics * 4 + i * 2))
+            .collect();
+
+        self.vmtx = Some(VmtxTable { v_metrics, top_side_bearings });
+    }
+
+    /// Whether the font has its own vertical metrics rather than the
+    /// synthesized ones.
+    pub fn has_vertical_metrics(&self) -> bool {
+        self.vmtx.is_some()
+    }
+
+    /// Unscaled advance height and top side bearing from `vmtx`.
+    fn v_metric(&self, glyph_id: u32) -> Option<(u16, i16)> {
+        let vmtx = self.vmtx.as_ref()?;
+        let idx = glyph_id as usize;
+
+        match vmtx.v_metrics.get(idx) {
+            Some(metric) => Some((metric.advance_height, metric.top_side_bearing)),
+            None => {
+                let advance = vmtx.v_metrics.last()?.advance_height;
+                let tsb = *vmtx.top_side_bearings.get(idx - vmtx.v_metrics.len())?;
+                Some((advance, tsb))
+            }
+        }
+    }
+
+    /// Vertical advance, top side bearing and vertical origin of a glyph.
+    /// The origin comes from `VORG` when present, otherwise from the top
+    /// side bearing and the top of the outline.
+    pub fn vertical_metrics(&self, glyph_id: u32) -> VerticalMetrics {
+        let outline_top = self.glyph_bounds(glyph_id).map(|(_, _, _, y_max)| y_max);
+        let origin_x = (self.h_metric(glyph_id).0 / 2) as i16;
+
+        let synthesized_origin = self.hhea.ascender;
+        let synthesized_advance = (self.hhea.ascender as i32 - self.hhea.descender as i32).max(0) as u16;
+
+        let (advance_height, origin_y, top_side_bearing) = match (self.v_metric(glyph_id), outline_top) {
+            (Some((advance, tsb)), Some(top)) => (advance, top.saturating_add(tsb), tsb),
+            (Some((advance, tsb)), None) => (advance, synthesized_origin, tsb),
+            (None, top) => (synthesized_advance, synthesized_origin, top.map_or(0, |top| synthesized_origin.saturating_sub(top))),
+        };
+
+        let origin_y = self.vorg.as_ref().map_or(origin_y, |vorg| vorg.vert_origin_y(glyph_id));
+
+        VerticalMetrics { advance_height, top_side_bearing, origin_x, origin_y }
+    }
+}
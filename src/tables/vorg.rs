@@ -0,0 +1,40 @@
+use crate::font::TrueTypeFont;
+use crate::tables::common::{read_i16, read_u16};
+use crate::Vec;
+
+/// Vertical origins of CFF glyphs, which have no `glyf` bounding box to
+/// derive them from.
+#[derive(Debug, Clone)]
+pub(crate) struct VorgTable {
+    pub(crate) default_vert_origin_y: i16,
+    /// Glyph ids with an origin of their own, sorted by glyph id.
+    pub(crate) vert_origin_y_metrics: Vec<(u16, i16)>,
+}
+
+impl VorgTable {
+    pub(crate) fn vert_origin_y(&self, glyph_id: u32) -> i16 {
+        match self.vert_origin_y_metrics.binary_search_by_key(&glyph_id, |&(id, _)| id as u32) {
+            Ok(i) => self.vert_origin_y_metrics[i].1,
+            Err(_) => self.default_vert_origin_y,
+        }
+    }
+}
+
+impl TrueTypeFont {
+    pub(crate) fn load_vorg(&mut self, font_bytes: &[u8]) {
+        let Some(data) = self.table_data(b"VORG", font_bytes) else { return };
+        if data.len() < 8 || read_u16(&data, 0) != 1 {
+            return;
+        }
+
+        let count = read_u16(&data, 6) as usize;
+        let vert_origin_y_metrics = (0..count)
+            .map(|i| (read_u16(&data, 8 + i * 4), read_i16(&data, 10 + i * 4)))
+            .collect();
+
+        self.vorg = Some(VorgTable {
+            default_vert_origin_y: read_i16(&data, 4),
+            vert_origin_y_metrics,
+        });
+    }
+}